@@ -0,0 +1,21 @@
+-- SIGIL Registry — Migration 0005: DID key rotation history
+--
+-- Retains every public key a DID has used, with its validity window, so
+-- receipts signed before a rotation can still be verified afterwards.
+-- The current key lives in dids.public_key; this table only holds retired keys.
+
+CREATE TABLE IF NOT EXISTS did_key_history (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    did         TEXT NOT NULL REFERENCES dids(did),
+
+    -- The retired Ed25519 public key, base64url-encoded
+    public_key  TEXT NOT NULL,
+
+    -- When this key became the DID's active key (registration or previous rotation)
+    valid_from  TIMESTAMPTZ NOT NULL,
+
+    -- When this key was rotated out
+    valid_until TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_did_key_history_did ON did_key_history(did, valid_until);
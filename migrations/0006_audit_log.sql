@@ -0,0 +1,54 @@
+-- SIGIL Registry — Migration 0006: Tamper-evident audit log
+--
+-- Append-only, hash-chained record of every registry mutation (DID lifecycle,
+-- submissions, votes, moderation). Each entry commits to the previous entry's
+-- hash, and each head is authenticated with the server's HMAC key, so any
+-- edit, deletion or reordering is detectable by replaying the chain.
+
+CREATE TABLE IF NOT EXISTS audit_log (
+    -- Gapless position in the chain, starting at 1
+    seq         BIGINT PRIMARY KEY,
+
+    -- Microsecond precision — part of the hashed content
+    occurred_at TIMESTAMPTZ NOT NULL,
+
+    -- What happened, e.g. 'did.registered', 'pattern.submitted', 'vote.cast'
+    action      TEXT NOT NULL,
+
+    -- What it happened to: a DID or an entry UUID
+    subject     TEXT NOT NULL,
+
+    -- Who did it: 'ip:{addr}', 'key:{id}' or a did:sigil: identifier
+    actor       TEXT,
+
+    -- Action-specific details
+    payload     JSONB NOT NULL DEFAULT '{}'::jsonb,
+
+    -- Hex SHA-256 of the previous entry (64 zeros for the first entry)
+    prev_hash   TEXT NOT NULL,
+
+    -- Hex SHA-256 over prev_hash and this entry's content
+    entry_hash  TEXT NOT NULL UNIQUE,
+
+    -- Hex HMAC-SHA256 of '{seq}:{entry_hash}' under AUDIT_HMAC_KEY (NULL in dev mode)
+    head_mac    TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_subject ON audit_log(subject);
+
+-- Reject any UPDATE, DELETE or TRUNCATE — the log only grows.
+CREATE OR REPLACE FUNCTION audit_log_append_only() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS audit_log_append_only ON audit_log;
+CREATE TRIGGER audit_log_append_only
+    BEFORE UPDATE OR DELETE ON audit_log
+    FOR EACH ROW EXECUTE FUNCTION audit_log_append_only();
+
+DROP TRIGGER IF EXISTS audit_log_no_truncate ON audit_log;
+CREATE TRIGGER audit_log_no_truncate
+    BEFORE TRUNCATE ON audit_log
+    FOR EACH STATEMENT EXECUTE FUNCTION audit_log_append_only();
@@ -0,0 +1,12 @@
+-- SIGIL Registry — Migration 0007: Registration nonces
+--
+-- Server-issued, single-use nonces for the proof-of-possession signature on
+-- POST /register. A nonce is deleted when consumed or once it expires.
+
+CREATE TABLE IF NOT EXISTS registration_nonces (
+    nonce      TEXT PRIMARY KEY,
+    issued_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_registration_nonces_expiry ON registration_nonces(expires_at);
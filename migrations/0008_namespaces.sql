@@ -0,0 +1,27 @@
+-- SIGIL Registry — Migration 0008: Namespace ownership
+--
+-- Namespaces become first-class objects owned by a controller DID. Once a
+-- namespace is claimed, registering a DID in it requires a signature from the
+-- controller or one of its delegates. Unclaimed namespaces behave as before.
+
+CREATE TABLE IF NOT EXISTS namespaces (
+    -- Namespace name, e.g. "enterprise_gateway"
+    name           TEXT PRIMARY KEY,
+
+    -- DID that controls registrations, delegation and transfer
+    controller_did TEXT NOT NULL REFERENCES dids(did),
+
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at     TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_namespaces_controller ON namespaces(controller_did);
+
+-- Sub-controllers allowed to authorise registrations on the controller's behalf
+CREATE TABLE IF NOT EXISTS namespace_delegates (
+    namespace    TEXT NOT NULL REFERENCES namespaces(name) ON DELETE CASCADE,
+    delegate_did TEXT NOT NULL REFERENCES dids(did) ON DELETE CASCADE,
+    granted_at   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (namespace, delegate_did)
+);
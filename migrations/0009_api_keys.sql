@@ -0,0 +1,29 @@
+-- SIGIL Registry — Migration 0009: Scoped API keys
+--
+-- Independent credentials for CI pipelines and tenants, replacing the single
+-- shared REGISTRY_KEY (which remains as the bootstrap/root key). Only a
+-- SHA-256 hash of each key is stored; the plaintext is shown once on issue.
+
+CREATE TABLE IF NOT EXISTS api_keys (
+    id           UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Human-readable name, e.g. "ci-gateway-eu"
+    name         TEXT NOT NULL,
+
+    -- First characters of the key, for identification in listings
+    key_prefix   TEXT NOT NULL,
+
+    -- Hex SHA-256 of the full key
+    key_hash     TEXT NOT NULL UNIQUE,
+
+    -- Subset of: 'register' | 'revoke' | 'moderate' | 'read-private' | 'admin'
+    scopes       TEXT[] NOT NULL,
+
+    -- Namespaces this key may act in (NULL = unrestricted)
+    namespaces   TEXT[],
+
+    expires_at   TIMESTAMPTZ,
+    last_used_at TIMESTAMPTZ,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    revoked_at   TIMESTAMPTZ
+);
@@ -0,0 +1,26 @@
+-- SIGIL Registry — Migration 0010: Maintainer moderation
+--
+-- Records the outcome of maintainer review on patterns and policies: who
+-- moderated the entry, when, and why. `verified` stays the flag the bundle
+-- reads; `review_status` distinguishes "not yet reviewed" from "rejected".
+
+ALTER TABLE scanner_patterns
+    -- 'pending' | 'approved' | 'rejected'
+    ADD COLUMN IF NOT EXISTS review_status     TEXT NOT NULL DEFAULT 'pending',
+    -- did:sigil: of the maintainer who last approved, rejected or deactivated the entry
+    ADD COLUMN IF NOT EXISTS moderated_by      TEXT,
+    ADD COLUMN IF NOT EXISTS moderated_at      TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS moderation_reason TEXT;
+
+ALTER TABLE security_policies
+    ADD COLUMN IF NOT EXISTS review_status     TEXT NOT NULL DEFAULT 'pending',
+    ADD COLUMN IF NOT EXISTS moderated_by      TEXT,
+    ADD COLUMN IF NOT EXISTS moderated_at      TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS moderation_reason TEXT;
+
+-- Seeded and hand-verified entries predate moderation
+UPDATE scanner_patterns  SET review_status = 'approved' WHERE verified = TRUE AND review_status = 'pending';
+UPDATE security_policies SET review_status = 'approved' WHERE verified = TRUE AND review_status = 'pending';
+
+CREATE INDEX IF NOT EXISTS idx_patterns_review ON scanner_patterns(review_status)  WHERE active = TRUE;
+CREATE INDEX IF NOT EXISTS idx_policies_review ON security_policies(review_status) WHERE active = TRUE;
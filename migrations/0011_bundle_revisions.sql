@@ -0,0 +1,23 @@
+-- SIGIL Registry — Migration 0011: Bundle revisions
+--
+-- One row per distinct content of a published bundle. The revision number
+-- only advances when the bundled set changes, so clients can cache by
+-- revision / ETag instead of re-downloading on every start.
+
+CREATE TABLE IF NOT EXISTS bundle_revisions (
+    -- Which bundle: 'patterns'
+    bundle         TEXT NOT NULL,
+
+    -- Monotonic per bundle, starting at 1
+    revision       BIGINT NOT NULL,
+
+    -- Hex SHA-256 of the serialised entries; served as the ETag
+    content_sha256 TEXT NOT NULL,
+
+    -- Newest updated_at of the bundled set when the revision was created
+    last_modified  TIMESTAMPTZ NOT NULL,
+
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (bundle, revision)
+);
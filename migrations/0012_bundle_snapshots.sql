@@ -0,0 +1,9 @@
+-- SIGIL Registry — Migration 0012: Bundle revision snapshots
+--
+-- Keeps the bundled entries of recent revisions so clients can fetch a delta
+-- (`GET /patterns/bundle/delta?since={revision}`) instead of the full bundle.
+-- Snapshots of old revisions are pruned to NULL; clients behind that far are
+-- told to resync in full.
+
+ALTER TABLE bundle_revisions
+    ADD COLUMN IF NOT EXISTS entries JSONB;
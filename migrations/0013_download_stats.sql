@@ -0,0 +1,22 @@
+-- SIGIL Registry — Migration 0013: Download events and daily stats
+--
+-- A bundle download used to UPDATE every verified pattern row. Downloads are
+-- now appended as events and periodically folded into per-pattern daily
+-- counts and scanner_patterns.downloads by a background aggregator.
+
+CREATE TABLE IF NOT EXISTS pattern_download_events (
+    id            BIGSERIAL PRIMARY KEY,
+
+    -- Pattern bundle revision that was served
+    revision      BIGINT NOT NULL,
+
+    downloaded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS pattern_downloads_daily (
+    pattern_id UUID NOT NULL REFERENCES scanner_patterns(id) ON DELETE CASCADE,
+    day        DATE NOT NULL,
+    downloads  BIGINT NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (pattern_id, day)
+);
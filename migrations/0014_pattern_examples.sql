@@ -0,0 +1,9 @@
+-- SIGIL Registry — Migration 0014: Pattern test examples
+--
+-- Submitted should_match / should_not_match examples, checked against the
+-- regex at submission and kept for reviewers and client regression tests.
+-- Examples of pii / financial patterns are stored as synthetic stand-ins.
+-- Shape: { "should_match": [..], "should_not_match": [..], "redacted": n }
+
+ALTER TABLE scanner_patterns
+    ADD COLUMN IF NOT EXISTS examples JSONB;
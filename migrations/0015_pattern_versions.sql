@@ -0,0 +1,42 @@
+-- SIGIL Registry — Migration 0015: Pattern versions
+--
+-- Authors may edit their patterns with PUT /patterns/:id. Each edit bumps
+-- `version` on the row, which always holds the latest version, and copies the
+-- version it replaces into scanner_pattern_versions together with its review
+-- outcome. While an edit awaits review (verified = FALSE), the bundle keeps
+-- serving the newest retained version that was verified.
+
+ALTER TABLE scanner_patterns
+    ADD COLUMN IF NOT EXISTS version            INT NOT NULL DEFAULT 1,
+    -- When the current version was submitted (created_at is the first version)
+    ADD COLUMN IF NOT EXISTS version_created_at TIMESTAMPTZ;
+
+UPDATE scanner_patterns SET version_created_at = created_at WHERE version_created_at IS NULL;
+
+ALTER TABLE scanner_patterns
+    ALTER COLUMN version_created_at SET DEFAULT NOW(),
+    ALTER COLUMN version_created_at SET NOT NULL;
+
+CREATE TABLE IF NOT EXISTS scanner_pattern_versions (
+    pattern_id        UUID        NOT NULL REFERENCES scanner_patterns(id) ON DELETE CASCADE,
+    version           INT         NOT NULL,
+    description       TEXT,
+    category          TEXT        NOT NULL,
+    pattern           TEXT        NOT NULL,
+    replacement_hint  TEXT,
+    severity          TEXT        NOT NULL,
+    examples          JSONB,
+    -- Review outcome of this version when it was superseded
+    verified          BOOLEAN     NOT NULL,
+    review_status     TEXT        NOT NULL,
+    moderated_by      TEXT,
+    moderated_at      TIMESTAMPTZ,
+    moderation_reason TEXT,
+    created_at        TIMESTAMPTZ NOT NULL,
+    superseded_at     TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (pattern_id, version)
+);
+
+-- Bundle fallback: newest verified version of a pattern under re-review
+CREATE INDEX IF NOT EXISTS idx_pattern_versions_verified
+    ON scanner_pattern_versions(pattern_id, version DESC) WHERE verified = TRUE;
@@ -0,0 +1,14 @@
+-- SIGIL Registry — Migration 0016: Signature nonces
+--
+-- Used nonces of v1-signed submissions and votes, when Redis is unavailable.
+-- A (signer, nonce) pair is accepted once; rows expire after twice the
+-- clock-skew window, when issued_at alone rejects the message.
+
+CREATE TABLE IF NOT EXISTS signature_nonces (
+    signer_did TEXT        NOT NULL,
+    nonce      TEXT        NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signer_did, nonce)
+);
+
+CREATE INDEX IF NOT EXISTS idx_signature_nonces_expiry ON signature_nonces(expires_at);
@@ -0,0 +1,64 @@
+-- SIGIL Registry — Migration 0017: Reputation-weighted votes
+--
+-- Every vote is weighted by its voter's reputation (0–100, see reputation.rs)
+-- at the time it was cast. `score` on patterns and policies is the sum of
+-- up-vote weights minus down-vote weights and orders the public listings; it
+-- is kept next to `votes_up` / `votes_down` and reconciled with them.
+
+ALTER TABLE registry_votes    ADD COLUMN IF NOT EXISTS weight INT NOT NULL DEFAULT 0;
+ALTER TABLE scanner_patterns  ADD COLUMN IF NOT EXISTS score  INT NOT NULL DEFAULT 0;
+ALTER TABLE security_policies ADD COLUMN IF NOT EXISTS score  INT NOT NULL DEFAULT 0;
+
+-- Earlier votes are weighted by their voter's reputation as of this migration,
+-- using the same formula as reputation.rs
+WITH contributions AS (
+    SELECT author_did AS did, COUNT(*) AS n FROM (
+        SELECT author_did FROM scanner_patterns  WHERE verified = TRUE AND active = TRUE
+        UNION ALL
+        SELECT author_did FROM security_policies WHERE verified = TRUE AND active = TRUE
+    ) c
+    WHERE author_did IS NOT NULL
+    GROUP BY author_did
+),
+targets AS (
+    SELECT 'pattern' AS target_type, id, verified, active, review_status, moderated_at FROM scanner_patterns
+    UNION ALL
+    SELECT 'policy', id, verified, active, review_status, moderated_at FROM security_policies
+),
+accepted AS (
+    SELECT v.voter_did AS did, COUNT(*) AS n
+    FROM registry_votes v
+    JOIN targets t ON t.target_type = v.target_type AND t.id = v.target_id
+    WHERE t.moderated_at IS NOT NULL AND v.voted_at < t.moderated_at
+      AND ((v.vote = 'up'   AND t.verified = TRUE)
+        OR (v.vote = 'down' AND (t.review_status = 'rejected' OR t.active = FALSE)))
+    GROUP BY v.voter_did
+),
+reputation AS (
+    SELECT d.did,
+           LEAST(30, FLOOR(EXTRACT(EPOCH FROM NOW() - d.created_at) / 86400))::INT
+         + LEAST(50, 10 * COALESCE(c.n, 0))::INT
+         + LEAST(20,  2 * COALESCE(a.n, 0))::INT AS points
+    FROM dids d
+    LEFT JOIN contributions c ON c.did = d.did
+    LEFT JOIN accepted a      ON a.did = d.did
+)
+UPDATE registry_votes v SET weight = r.points
+FROM reputation r WHERE r.did = v.voter_did;
+
+UPDATE scanner_patterns p SET score = s.score
+FROM (
+    SELECT target_id, SUM(CASE vote WHEN 'up' THEN weight ELSE -weight END)::INT AS score
+    FROM registry_votes WHERE target_type = 'pattern' GROUP BY target_id
+) s
+WHERE s.target_id = p.id;
+
+UPDATE security_policies p SET score = s.score
+FROM (
+    SELECT target_id, SUM(CASE vote WHEN 'up' THEN weight ELSE -weight END)::INT AS score
+    FROM registry_votes WHERE target_type = 'policy' GROUP BY target_id
+) s
+WHERE s.target_id = p.id;
+
+CREATE INDEX IF NOT EXISTS idx_patterns_score ON scanner_patterns(score DESC)  WHERE active = TRUE;
+CREATE INDEX IF NOT EXISTS idx_policies_score ON security_policies(score DESC) WHERE active = TRUE;
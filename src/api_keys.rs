@@ -0,0 +1,282 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Scoped API keys presented in the `X-Registry-Key` header.
+//!
+//! The `REGISTRY_KEY` environment variable is the root key: it carries every
+//! scope and is used to bootstrap further keys through `POST /admin/keys`.
+//! Issued keys live hashed in `api_keys`, each with its own scopes, optional
+//! namespace restriction and expiry.
+
+use crate::{db::AppState, error::RegistryError};
+use axum::http::HeaderMap;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Key id reported for the root `REGISTRY_KEY`.
+pub const ROOT_KEY_ID: &str = "registry";
+
+/// What an API key is allowed to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// Register DIDs and change their labels
+    Register,
+    /// Revoke DIDs
+    Revoke,
+    /// Moderate patterns, policies and namespace disputes
+    Moderate,
+    /// Read non-public data (e.g. client IPs in DID history)
+    ReadPrivate,
+    /// Issue and revoke API keys
+    Admin,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 5] =
+        [Scope::Register, Scope::Revoke, Scope::Moderate, Scope::ReadPrivate, Scope::Admin];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Scope::Register => "register",
+            Scope::Revoke => "revoke",
+            Scope::Moderate => "moderate",
+            Scope::ReadPrivate => "read-private",
+            Scope::Admin => "admin",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Scope> {
+        Scope::ALL.into_iter().find(|scope| scope.as_str() == s)
+    }
+}
+
+/// An `api_keys` row, without its hash.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub name: String,
+    pub key_prefix: String,
+    pub scopes: Vec<String>,
+    pub namespaces: Option<Vec<String>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// The authenticated caller behind an `X-Registry-Key`.
+#[derive(Debug, Clone)]
+pub struct Principal {
+    pub key_id: String,
+    pub scopes: Vec<String>,
+    /// `None` = any namespace
+    pub namespaces: Option<Vec<String>>,
+}
+
+impl Principal {
+    fn root() -> Self {
+        Self {
+            key_id: ROOT_KEY_ID.to_string(),
+            scopes: Scope::ALL.iter().map(|s| s.as_str().to_string()).collect(),
+            namespaces: None,
+        }
+    }
+
+    /// The `actor` recorded in audit trails for this key.
+    pub fn actor(&self) -> String {
+        format!("key:{}", self.key_id)
+    }
+
+    pub fn has(&self, scope: Scope) -> bool {
+        self.scopes.iter().any(|s| s == scope.as_str())
+    }
+
+    /// Fail with `Forbidden` unless the key carries `scope`.
+    pub fn require(&self, scope: Scope) -> Result<(), RegistryError> {
+        if !self.has(scope) {
+            return Err(RegistryError::Forbidden(format!(
+                "API key '{}' lacks the '{}' scope",
+                self.key_id,
+                scope.as_str()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether this is the root `REGISTRY_KEY`.
+    pub fn is_root(&self) -> bool {
+        self.key_id == ROOT_KEY_ID
+    }
+
+    /// Fail with `Forbidden` unless a key with `scopes` and `namespaces` grants
+    /// nothing this key does not hold itself.
+    ///
+    /// Only the root key may issue keys without a namespace restriction.
+    pub fn require_delegable(&self, scopes: &[String], namespaces: Option<&[String]>) -> Result<(), RegistryError> {
+        if let Some(extra) = scopes.iter().find(|s| !self.scopes.contains(s)) {
+            return Err(RegistryError::Forbidden(format!(
+                "API key '{}' cannot grant the '{extra}' scope it does not hold",
+                self.key_id
+            )));
+        }
+        match (namespaces, &self.namespaces) {
+            (None, _) if !self.is_root() => Err(RegistryError::Forbidden(
+                "only the root key can issue keys without a namespace restriction".into(),
+            )),
+            (Some(requested), Some(allowed)) => match requested.iter().find(|ns| !allowed.contains(ns)) {
+                Some(ns) => Err(RegistryError::Forbidden(format!(
+                    "API key '{}' cannot grant namespace '{ns}' it is not allowed in",
+                    self.key_id
+                ))),
+                None => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// Fail with `Forbidden` unless the key may act in `namespace`.
+    pub fn require_namespace(&self, namespace: &str) -> Result<(), RegistryError> {
+        match &self.namespaces {
+            Some(allowed) if !allowed.iter().any(|ns| ns == namespace) => {
+                Err(RegistryError::Forbidden(format!(
+                    "API key '{}' is not allowed in namespace '{namespace}'",
+                    self.key_id
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Identify the caller from `X-Registry-Key`.
+///
+/// Returns `Ok(None)` when no key is supplied and `Unauthorized` when a key is
+/// supplied but unknown, revoked or expired.
+pub async fn authenticate(
+    state: &AppState,
+    headers: &HeaderMap,
+) -> Result<Option<Principal>, RegistryError> {
+    let Some(supplied) = headers.get("x-registry-key").and_then(|v| v.to_str().ok()) else {
+        return Ok(None);
+    };
+
+    // Constant-time comparison to resist timing attacks
+    if let Some(root) = &state.registry_key {
+        if constant_time_eq(supplied.as_bytes(), root.as_bytes()) {
+            return Ok(Some(Principal::root()));
+        }
+    }
+
+    // Issued keys are looked up by hash, so the comparison never sees the secret
+    let row: Option<(Uuid, Vec<String>, Option<Vec<String>>)> = sqlx::query_as(
+        "SELECT id, scopes, namespaces FROM api_keys
+         WHERE key_hash = $1
+           AND revoked_at IS NULL
+           AND (expires_at IS NULL OR expires_at > NOW())",
+    )
+    .bind(hash_key(supplied))
+    .fetch_optional(&state.pool)
+    .await?;
+
+    let Some((id, scopes, namespaces)) = row else {
+        tracing::warn!("Rejected request: invalid, revoked or expired X-Registry-Key");
+        return Err(RegistryError::Unauthorized);
+    };
+
+    // Fire-and-forget: record usage without blocking the request
+    let pool = state.pool.clone();
+    tokio::spawn(async move {
+        let _ = sqlx::query("UPDATE api_keys SET last_used_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(&pool)
+            .await;
+    });
+
+    Ok(Some(Principal { key_id: id.to_string(), scopes, namespaces }))
+}
+
+/// Generate a fresh API key. Returned once to the caller; only its hash is stored.
+pub fn generate_key() -> String {
+    format!("sgk_{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// Hex SHA-256 of an API key — keys are high-entropy, so no slow KDF is needed.
+pub fn hash_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+/// Constant-time byte slice comparison.
+///
+/// Returns `true` only if both slices are equal AND of the same length.
+/// Always iterates the full length of the longer slice to prevent timing leaks.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scopes_round_trip() {
+        for scope in Scope::ALL {
+            assert_eq!(Scope::parse(scope.as_str()), Some(scope));
+        }
+        assert_eq!(Scope::parse("superuser"), None);
+    }
+
+    #[test]
+    fn principal_enforces_scope_and_namespace() {
+        let p = Principal {
+            key_id: "ci".into(),
+            scopes: vec!["register".into()],
+            namespaces: Some(vec!["acme".into()]),
+        };
+        assert!(p.require(Scope::Register).is_ok());
+        assert!(p.require(Scope::Revoke).is_err());
+        assert!(p.require_namespace("acme").is_ok());
+        assert!(p.require_namespace("other").is_err());
+
+        let root = Principal::root();
+        assert!(Scope::ALL.iter().all(|s| root.has(*s)));
+        assert!(root.require_namespace("anything").is_ok());
+    }
+
+    #[test]
+    fn issued_keys_cannot_exceed_the_issuer() {
+        let strings = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let admin = Principal {
+            key_id: "tenant-admin".into(),
+            scopes: strings(&["admin", "register"]),
+            namespaces: Some(strings(&["acme"])),
+        };
+        assert!(admin.require_delegable(&strings(&["register"]), Some(&strings(&["acme"]))).is_ok());
+        assert!(admin.require_delegable(&strings(&["revoke"]), Some(&strings(&["acme"]))).is_err());
+        assert!(admin.require_delegable(&strings(&["register"]), Some(&strings(&["acme", "other"]))).is_err());
+        assert!(admin.require_delegable(&strings(&["register"]), None).is_err());
+
+        let unrestricted = Principal { namespaces: None, ..admin };
+        assert!(unrestricted.require_delegable(&strings(&["register"]), Some(&strings(&["other"]))).is_ok());
+        assert!(unrestricted.require_delegable(&strings(&["register"]), None).is_err());
+
+        let root = Principal::root();
+        assert!(root.require_delegable(&strings(&["admin", "moderate"]), None).is_ok());
+    }
+
+    #[test]
+    fn generated_keys_are_unique_and_hash_stably() {
+        let (a, b) = (generate_key(), generate_key());
+        assert_ne!(a, b);
+        assert_eq!(a.len(), 4 + 64);
+        assert_eq!(hash_key(&a), hash_key(&a));
+        assert_ne!(hash_key(&a), hash_key(&b));
+    }
+}
@@ -0,0 +1,246 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Hash-chained, tamper-evident audit log of registry mutations.
+//!
+//! Every entry in `audit_log` commits to its predecessor:
+//!
+//! ```text
+//! entry_hash = hex(SHA-256(prev_hash ":" content))
+//! content    = JSON [seq, occurred_at_micros, action, subject, actor, payload]
+//! head_mac   = hex(HMAC-SHA256(AUDIT_HMAC_KEY, "{seq}:{entry_hash}"))
+//! ```
+//!
+//! The first entry uses [`GENESIS_HASH`] as `prev_hash`. Editing, deleting or
+//! reordering any entry breaks every later link; [`verify_chain`] replays the
+//! chain offline (e.g. over an export of `GET /audit/entries`) and reports the
+//! first broken link.
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use sqlx::PgConnection;
+
+/// `prev_hash` of the first entry in the chain.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Advisory lock key serialising appends, so two writers never chain onto the same head.
+const APPEND_LOCK_KEY: i64 = 0x5349_4749_4c41_5544; // "SIGILAUD"
+
+/// A single entry of the audit chain.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AuditEntry {
+    pub seq: i64,
+    pub occurred_at: DateTime<Utc>,
+    pub action: String,
+    pub subject: String,
+    pub actor: Option<String>,
+    pub payload: Value,
+    pub prev_hash: String,
+    pub entry_hash: String,
+    pub head_mac: Option<String>,
+}
+
+/// The first link at which [`verify_chain`] found the chain inconsistent.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("chain broken at seq {seq}: {reason}")]
+pub struct ChainBreak {
+    pub seq: i64,
+    pub reason: String,
+}
+
+/// Compute the hash committing an entry's content to its predecessor.
+pub fn entry_hash(
+    prev_hash: &str,
+    seq: i64,
+    occurred_at: DateTime<Utc>,
+    action: &str,
+    subject: &str,
+    actor: Option<&str>,
+    payload: &Value,
+) -> String {
+    // serde_json orders object keys, so the content serialises deterministically
+    let content = json!([seq, occurred_at.timestamp_micros(), action, subject, actor, payload]);
+    let mut hasher = Sha256::new();
+    hasher.update(prev_hash.as_bytes());
+    hasher.update(b":");
+    hasher.update(content.to_string().as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Authenticate a chain head with the server's audit key.
+pub fn head_mac(key: &[u8], seq: i64, entry_hash: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(format!("{seq}:{entry_hash}").as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Append an entry to the audit chain.
+///
+/// Must be called inside a transaction: the advisory lock taken here is held
+/// until commit, and the entry should commit or roll back together with the
+/// mutation it records.
+pub async fn append(
+    conn: &mut PgConnection,
+    key: Option<&[u8]>,
+    action: &str,
+    subject: &str,
+    actor: Option<&str>,
+    payload: Value,
+) -> Result<AuditEntry, sqlx::Error> {
+    sqlx::query("SELECT pg_advisory_xact_lock($1)")
+        .bind(APPEND_LOCK_KEY)
+        .execute(&mut *conn)
+        .await?;
+
+    let head: Option<(i64, String)> =
+        sqlx::query_as("SELECT seq, entry_hash FROM audit_log ORDER BY seq DESC LIMIT 1")
+            .fetch_optional(&mut *conn)
+            .await?;
+    let (prev_seq, prev_hash) = head.unwrap_or((0, GENESIS_HASH.to_string()));
+
+    let seq = prev_seq + 1;
+    // Truncate to the microsecond precision PostgreSQL stores, so the hash replays
+    let now = Utc::now();
+    let occurred_at = DateTime::from_timestamp_micros(now.timestamp_micros()).unwrap_or(now);
+    let hash = entry_hash(&prev_hash, seq, occurred_at, action, subject, actor, &payload);
+    let mac = key.map(|k| head_mac(k, seq, &hash));
+
+    sqlx::query(
+        "INSERT INTO audit_log
+           (seq, occurred_at, action, subject, actor, payload, prev_hash, entry_hash, head_mac)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+    )
+    .bind(seq)
+    .bind(occurred_at)
+    .bind(action)
+    .bind(subject)
+    .bind(actor)
+    .bind(&payload)
+    .bind(&prev_hash)
+    .bind(&hash)
+    .bind(&mac)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(AuditEntry {
+        seq,
+        occurred_at,
+        action: action.to_string(),
+        subject: subject.to_string(),
+        actor: actor.map(str::to_string),
+        payload,
+        prev_hash,
+        entry_hash: hash,
+        head_mac: mac,
+    })
+}
+
+/// Replay a contiguous run of entries and report the first broken link.
+///
+/// - `prev_hash` — hash of the entry preceding `entries[0]` ([`GENESIS_HASH`]
+///   when replaying from the start)
+/// - `key`       — the audit HMAC key; when given, every `head_mac` must verify
+pub fn verify_chain(
+    entries: &[AuditEntry],
+    prev_hash: &str,
+    key: Option<&[u8]>,
+) -> Result<(), ChainBreak> {
+    let mut expected_prev = prev_hash.to_string();
+    let mut expected_seq = entries.first().map(|e| e.seq);
+
+    for e in entries {
+        let fail = |reason: String| Err(ChainBreak { seq: e.seq, reason });
+
+        if Some(e.seq) != expected_seq {
+            return fail(format!("expected seq {}", expected_seq.unwrap_or_default()));
+        }
+        if e.prev_hash != expected_prev {
+            return fail("prev_hash does not match the preceding entry".into());
+        }
+
+        let computed = entry_hash(
+            &e.prev_hash,
+            e.seq,
+            e.occurred_at,
+            &e.action,
+            &e.subject,
+            e.actor.as_deref(),
+            &e.payload,
+        );
+        if computed != e.entry_hash {
+            return fail("entry content does not match entry_hash".into());
+        }
+
+        if let Some(key) = key {
+            match &e.head_mac {
+                Some(mac) if *mac == head_mac(key, e.seq, &e.entry_hash) => {}
+                Some(_) => return fail("head_mac does not verify".into()),
+                None => return fail("head_mac missing".into()),
+            }
+        }
+
+        expected_prev = e.entry_hash.clone();
+        expected_seq = Some(e.seq + 1);
+    }
+
+    Ok(())
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &[u8] = b"test-audit-key";
+
+    fn chain(len: i64) -> Vec<AuditEntry> {
+        let mut prev = GENESIS_HASH.to_string();
+        (1..=len)
+            .map(|seq| {
+                let occurred_at = DateTime::from_timestamp_micros(1_700_000_000_000_000 + seq).unwrap();
+                let payload = json!({ "n": seq });
+                let hash = entry_hash(&prev, seq, occurred_at, "vote.cast", "x", Some("did:sigil:a"), &payload);
+                let entry = AuditEntry {
+                    seq,
+                    occurred_at,
+                    action: "vote.cast".into(),
+                    subject: "x".into(),
+                    actor: Some("did:sigil:a".into()),
+                    payload,
+                    prev_hash: prev.clone(),
+                    head_mac: Some(head_mac(KEY, seq, &hash)),
+                    entry_hash: hash,
+                };
+                prev = entry.entry_hash.clone();
+                entry
+            })
+            .collect()
+    }
+
+    #[test]
+    fn intact_chain_verifies() {
+        let entries = chain(5);
+        assert_eq!(verify_chain(&entries, GENESIS_HASH, Some(KEY)), Ok(()));
+        // A suffix verifies against its predecessor's hash
+        assert_eq!(verify_chain(&entries[2..], &entries[1].entry_hash, Some(KEY)), Ok(()));
+    }
+
+    #[test]
+    fn reports_first_broken_link() {
+        let mut entries = chain(5);
+        entries[2].payload = json!({ "n": 99 });
+        assert_eq!(verify_chain(&entries, GENESIS_HASH, None).unwrap_err().seq, 3);
+
+        let mut entries = chain(5);
+        entries.remove(1);
+        assert_eq!(verify_chain(&entries, GENESIS_HASH, None).unwrap_err().seq, 3);
+
+        let entries = chain(3);
+        assert_eq!(verify_chain(&entries, GENESIS_HASH, Some(b"wrong")).unwrap_err().seq, 1);
+    }
+}
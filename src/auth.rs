@@ -20,25 +20,76 @@
 //! sigil-registry:policy:{tool_name}:{risk_level}:{requires_trust}:{author_did}
 //! ```
 //!
-//! For votes:
+//! For new versions of a pattern (by its original author):
+//! ```text
+//! sigil-registry:pattern-update:{id}:{version}:{category}:{pattern}:{author_did}
+//! ```
+//!
+//! For votes (`vote` is `up` | `down`):
 //! ```text
 //! sigil-registry:vote:{target_type}:{target_id}:{vote}:{voter_did}
 //! ```
+//!
+//! These four are the v0 formats and cover only some of the submitted fields.
+//! Clients should instead sign the whole request body in canonical JSON, with
+//! `audience`, `issued_at` and `nonce` added (v1) — see [`crate::replay`].
+//! Changing or retracting a vote is only accepted as v1.
+//!
+//! For DID registration (proof of possession; `nonce` from `GET /register/nonce`):
+//! ```text
+//! sigil-registry:register:{did}:{public_key}:{nonce}
+//! ```
+//!
+//! For registration in a claimed namespace (signed by its controller or a delegate):
+//! ```text
+//! sigil-registry:namespace-register:{namespace}:{did}:{public_key}:{nonce}
+//! ```
+//!
+//! For namespace management (signed by the claiming / current controller):
+//! ```text
+//! sigil-registry:namespace-claim:{namespace}:{controller_did}:{timestamp}
+//! sigil-registry:namespace-transfer:{namespace}:{new_controller_did}:{timestamp}:{nonce}
+//! sigil-registry:namespace-delegate:{namespace}:{delegate_did}:{grant|revoke}:{timestamp}:{nonce}
+//! ```
+//!
+//! For self-revocation of a DID (`timestamp` is Unix seconds):
+//! ```text
+//! sigil-registry:revoke:{did}:{timestamp}
+//! ```
+//!
+//! For label changes (`label` is empty when clearing it):
+//! ```text
+//! sigil-registry:label:{did}:{label}:{timestamp}:{nonce}
+//! ```
+//!
+//! For key rotation (signed by both the current and the new key):
+//! ```text
+//! sigil-registry:rotate:{did}:{new_public_key}:{timestamp}:{nonce}
+//! ```
+//!
+//! For maintainer moderation (`action` is `approve` | `reject` | `deactivate`):
+//! ```text
+//! sigil-registry:moderate:{target_type}:{target_id}:{action}:{reason}:{timestamp}:{nonce}
+//! ```
+//!
+//! A `nonce` in these messages is single-use per signer, like the nonce of a v1
+//! body (see [`crate::replay`]): a transfer, delegate change, label change,
+//! rotation or moderation decision could otherwise be replayed within the
+//! timestamp window to undo a later one.
 
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use ed25519_dalek::{Signature, VerifyingKey};
 
-/// Verify an Ed25519 signature over a message.
+/// Default maximum clock skew (seconds) between a signed request's timestamp
+/// and the server clock; configurable via `SIGNATURE_MAX_SKEW_SECS`
+/// (see [`crate::replay::ReplayPolicy`]).
+pub const TIMESTAMP_WINDOW_SECS: i64 = 300;
+
+/// Decode and validate a base64url-encoded Ed25519 public key.
 ///
-/// - `public_key_b64` — base64url-encoded 32-byte Ed25519 public key (from DID record)
-/// - `message`       — the canonical message that was signed
-/// - `signature_b64` — base64url-encoded 64-byte Ed25519 signature
-pub fn verify_signature(
-    public_key_b64: &str,
-    message: &str,
-    signature_b64: &str,
-) -> Result<(), String> {
-    // Decode public key
+/// Rejects keys that are not 32 bytes, do not decompress to a curve point, or
+/// are weak (small-order) points — any signature "verifies" under those.
+pub fn parse_public_key(public_key_b64: &str) -> Result<VerifyingKey, String> {
     let pk_bytes = URL_SAFE_NO_PAD
         .decode(public_key_b64)
         .map_err(|e| format!("bad public key encoding: {e}"))?;
@@ -50,6 +101,25 @@ pub fn verify_signature(
     let verifying_key =
         VerifyingKey::from_bytes(&pk_bytes).map_err(|e| format!("invalid public key: {e}"))?;
 
+    if verifying_key.is_weak() {
+        return Err("public key is a weak (small-order) point".into());
+    }
+
+    Ok(verifying_key)
+}
+
+/// Verify an Ed25519 signature over a message.
+///
+/// - `public_key_b64` — base64url-encoded 32-byte Ed25519 public key (from DID record)
+/// - `message`       — the canonical message that was signed
+/// - `signature_b64` — base64url-encoded 64-byte Ed25519 signature
+pub fn verify_signature(
+    public_key_b64: &str,
+    message: &str,
+    signature_b64: &str,
+) -> Result<(), String> {
+    let verifying_key = parse_public_key(public_key_b64)?;
+
     // Decode signature
     let sig_bytes = URL_SAFE_NO_PAD
         .decode(signature_b64)
@@ -73,6 +143,11 @@ pub fn pattern_message(name: &str, category: &str, pattern: &str, author_did: &s
     format!("sigil-registry:pattern:{name}:{category}:{pattern}:{author_did}")
 }
 
+/// Build the canonical message for a new version of an existing pattern.
+pub fn pattern_update_message(id: &str, version: i32, category: &str, pattern: &str, author_did: &str) -> String {
+    format!("sigil-registry:pattern-update:{id}:{version}:{category}:{pattern}:{author_did}")
+}
+
 /// Build the canonical message for a policy submission.
 pub fn policy_message(
     tool_name: &str,
@@ -87,3 +162,130 @@ pub fn policy_message(
 pub fn vote_message(target_type: &str, target_id: &str, vote: &str, voter_did: &str) -> String {
     format!("sigil-registry:vote:{target_type}:{target_id}:{vote}:{voter_did}")
 }
+
+/// Build the canonical proof-of-possession message for a DID registration.
+pub fn register_message(did: &str, public_key: &str, nonce: &str) -> String {
+    format!("sigil-registry:register:{did}:{public_key}:{nonce}")
+}
+
+/// Build the canonical message a namespace controller signs to authorise a registration.
+pub fn namespace_register_message(namespace: &str, did: &str, public_key: &str, nonce: &str) -> String {
+    format!("sigil-registry:namespace-register:{namespace}:{did}:{public_key}:{nonce}")
+}
+
+/// Build the canonical message for claiming a namespace.
+pub fn namespace_claim_message(namespace: &str, controller_did: &str, timestamp: i64) -> String {
+    format!("sigil-registry:namespace-claim:{namespace}:{controller_did}:{timestamp}")
+}
+
+/// Build the canonical message for transferring a namespace to a new controller.
+pub fn namespace_transfer_message(namespace: &str, new_controller_did: &str, timestamp: i64, nonce: &str) -> String {
+    format!("sigil-registry:namespace-transfer:{namespace}:{new_controller_did}:{timestamp}:{nonce}")
+}
+
+/// Build the canonical message for granting (`grant = true`) or revoking a delegate.
+pub fn namespace_delegate_message(
+    namespace: &str,
+    delegate_did: &str,
+    grant: bool,
+    timestamp: i64,
+    nonce: &str,
+) -> String {
+    let action = if grant { "grant" } else { "revoke" };
+    format!("sigil-registry:namespace-delegate:{namespace}:{delegate_did}:{action}:{timestamp}:{nonce}")
+}
+
+/// Build the canonical message for a DID self-revocation.
+pub fn revoke_message(did: &str, timestamp: i64) -> String {
+    format!("sigil-registry:revoke:{did}:{timestamp}")
+}
+
+/// Build the canonical message for a DID label change.
+pub fn label_message(did: &str, label: &str, timestamp: i64, nonce: &str) -> String {
+    format!("sigil-registry:label:{did}:{label}:{timestamp}:{nonce}")
+}
+
+/// Build the canonical message for a DID key rotation.
+pub fn rotate_message(did: &str, new_public_key: &str, timestamp: i64, nonce: &str) -> String {
+    format!("sigil-registry:rotate:{did}:{new_public_key}:{timestamp}:{nonce}")
+}
+
+/// Build the canonical message for a maintainer moderation decision.
+///
+/// `reason` is empty when none was given.
+pub fn moderation_message(
+    target_type: &str,
+    target_id: &str,
+    action: &str,
+    reason: &str,
+    timestamp: i64,
+    nonce: &str,
+) -> String {
+    format!("sigil-registry:moderate:{target_type}:{target_id}:{action}:{reason}:{timestamp}:{nonce}")
+}
+
+/// Check that a signed request's `timestamp` lies within `window` seconds
+/// of `now` (both Unix seconds).
+///
+/// Bounds how long a captured signature can be replayed.
+pub fn check_timestamp(timestamp: i64, now: i64, window: i64) -> Result<(), String> {
+    // `abs_diff` cannot overflow on an attacker-chosen timestamp
+    if now.abs_diff(timestamp) > window.max(0) as u64 {
+        return Err(format!(
+            "timestamp {timestamp} is outside the allowed window of {window}s"
+        ));
+    }
+    Ok(())
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn keypair() -> (SigningKey, String) {
+        let sk = SigningKey::from_bytes(&[7u8; 32]);
+        let pk = URL_SAFE_NO_PAD.encode(sk.verifying_key().as_bytes());
+        (sk, pk)
+    }
+
+    #[test]
+    fn revoke_signature_round_trip() {
+        let (sk, pk) = keypair();
+        let msg = revoke_message("did:sigil:alice", 1_700_000_000);
+        let sig = URL_SAFE_NO_PAD.encode(sk.sign(msg.as_bytes()).to_bytes());
+
+        assert!(verify_signature(&pk, &msg, &sig).is_ok());
+        let other = revoke_message("did:sigil:bob", 1_700_000_000);
+        assert!(verify_signature(&pk, &other, &sig).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_and_weak_keys() {
+        let (_, pk) = keypair();
+        assert!(parse_public_key(&pk).is_ok());
+        assert!(parse_public_key("not base64!").is_err());
+        assert!(parse_public_key(&URL_SAFE_NO_PAD.encode([1u8; 31])).is_err());
+
+        // The identity point (y = 1) has order 1
+        let mut identity = [0u8; 32];
+        identity[0] = 1;
+        let err = parse_public_key(&URL_SAFE_NO_PAD.encode(identity)).unwrap_err();
+        assert!(err.contains("weak"), "{err}");
+    }
+
+    #[test]
+    fn timestamp_window() {
+        let now = 1_700_000_000;
+        let window = TIMESTAMP_WINDOW_SECS;
+        assert!(check_timestamp(now, now, window).is_ok());
+        assert!(check_timestamp(now - window, now, window).is_ok());
+        assert!(check_timestamp(now - window - 1, now, window).is_err());
+        assert!(check_timestamp(now + window + 1, now, window).is_err());
+        assert!(check_timestamp(i64::MIN, now, window).is_err());
+        assert!(check_timestamp(i64::MAX, now, window).is_err());
+        assert!(check_timestamp(i64::MIN, i64::MAX, i64::MAX).is_err());
+    }
+}
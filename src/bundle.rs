@@ -0,0 +1,320 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Revisions and conditional responses for published bundles.
+//!
+//! A bundle's content hash is the SHA-256 of its serialised entries. Each
+//! distinct hash gets the next `revision` number in `bundle_revisions` the
+//! first time it is served; votes, downloads and other edits that do not
+//! change the bundle leave the revision, `ETag` and `Last-Modified` untouched.
+//!
+//! A revision has exactly one body, so its strong `ETag` is the revision
+//! number plus the content hash. Clients revalidate with `If-None-Match` and
+//! get `304 Not Modified` until the verified set changes; after A → B → A the
+//! entries are those of A again but the revision, and so the tag, is new.
+//!
+//! Each revision also stores a snapshot of its entries, so [`delta`] can tell
+//! clients what was added, changed or removed since the revision they hold.
+//! Only the last [`SNAPSHOT_RETENTION`] snapshots are kept; clients further
+//! behind must resync in full. A delta is tagged by the pair of revisions it
+//! spans ([`BundleRevision::delta_etag`]), never with the full bundle's tag.
+
+use crate::{bundle_signing, db::AppState};
+use axum::{
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+
+/// How often to retry when a concurrent request claims the same revision number.
+const REVISION_RETRIES: usize = 3;
+
+/// How many recent revisions keep their entry snapshot for deltas.
+pub const SNAPSHOT_RETENTION: i64 = 100;
+
+/// A row of `bundle_revisions`.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct BundleRevision {
+    pub revision: i64,
+    pub content_sha256: String,
+    /// Newest `updated_at` of the bundled set when the revision was created
+    pub last_modified: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl BundleRevision {
+    /// Strong entity tag for this revision's body.
+    pub fn etag(&self) -> String {
+        format!("\"{}-{}\"", self.revision, self.content_sha256)
+    }
+
+    /// Entity tag for the delta from revision `since` to this one.
+    ///
+    /// Weak, because the body turns into a `full_resync` once `since` ages out
+    /// of the retained snapshots.
+    pub fn delta_etag(&self, since: i64) -> String {
+        format!("W/\"delta-{since}-{}\"", self.revision)
+    }
+}
+
+/// Hex SHA-256 over the serialised bundle entries.
+pub fn content_hash<T: Serialize>(entries: &[T]) -> Result<String, serde_json::Error> {
+    Ok(hex::encode(Sha256::digest(serde_json::to_vec(entries)?)))
+}
+
+/// The revision for `content_sha256`, creating the next one if the content changed.
+///
+/// `last_modified` and the `entries` snapshot are only recorded when a new
+/// revision is created.
+pub async fn current_revision(
+    pool: &PgPool,
+    bundle: &str,
+    content_sha256: &str,
+    last_modified: DateTime<Utc>,
+    entries: &Value,
+) -> Result<BundleRevision, sqlx::Error> {
+    let mut latest = latest_revision(pool, bundle).await?;
+
+    for _ in 0..REVISION_RETRIES {
+        if let Some(rev) = latest.as_ref().filter(|r| r.content_sha256 == content_sha256) {
+            return Ok(rev.clone());
+        }
+
+        let next = latest.as_ref().map_or(1, |r| r.revision + 1);
+        let inserted = sqlx::query_as::<_, BundleRevision>(
+            "INSERT INTO bundle_revisions (bundle, revision, content_sha256, last_modified, entries)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (bundle, revision) DO NOTHING
+             RETURNING revision, content_sha256, last_modified, created_at",
+        )
+        .bind(bundle)
+        .bind(next)
+        .bind(content_sha256)
+        .bind(last_modified)
+        .bind(entries)
+        .fetch_optional(pool)
+        .await?;
+
+        if let Some(rev) = inserted {
+            sqlx::query(
+                "UPDATE bundle_revisions SET entries = NULL
+                 WHERE bundle = $1 AND revision <= $2 AND entries IS NOT NULL",
+            )
+            .bind(bundle)
+            .bind(rev.revision - SNAPSHOT_RETENTION)
+            .execute(pool)
+            .await?;
+
+            tracing::info!("Bundle '{bundle}' advanced to revision {}", rev.revision);
+            return Ok(rev);
+        }
+
+        // Another request created this revision first — re-read and compare again
+        latest = latest_revision(pool, bundle).await?;
+    }
+
+    latest.ok_or(sqlx::Error::RowNotFound)
+}
+
+/// The newest revision of `bundle`, without checking whether the content changed.
+pub async fn latest_revision(pool: &PgPool, bundle: &str) -> Result<Option<BundleRevision>, sqlx::Error> {
+    sqlx::query_as::<_, BundleRevision>(
+        "SELECT revision, content_sha256, last_modified, created_at
+         FROM bundle_revisions WHERE bundle = $1
+         ORDER BY revision DESC LIMIT 1",
+    )
+    .bind(bundle)
+    .fetch_optional(pool)
+    .await
+}
+
+/// The entries snapshot of an earlier revision, or `None` if it is unknown or pruned.
+pub async fn snapshot(pool: &PgPool, bundle: &str, revision: i64) -> Result<Option<Value>, sqlx::Error> {
+    let entries: Option<Option<Value>> = sqlx::query_scalar(
+        "SELECT entries FROM bundle_revisions WHERE bundle = $1 AND revision = $2",
+    )
+    .bind(bundle)
+    .bind(revision)
+    .fetch_optional(pool)
+    .await?;
+    Ok(entries.flatten())
+}
+
+/// Entries added, changed and removed between two snapshots.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct Delta {
+    pub added: Vec<Value>,
+    pub changed: Vec<Value>,
+    /// Keys of entries no longer in the bundle
+    pub removed: Vec<String>,
+}
+
+/// Diff two snapshots (JSON arrays of objects), matching entries on `key`.
+pub fn delta(old: &Value, new: &Value, key: &str) -> Delta {
+    let index = |v: &Value| -> std::collections::BTreeMap<String, Value> {
+        v.as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|e| Some((e.get(key)?.as_str()?.to_string(), e.clone())))
+            .collect()
+    };
+    let (old, new) = (index(old), index(new));
+
+    let mut out = Delta::default();
+    for (k, entry) in &new {
+        match old.get(k) {
+            None => out.added.push(entry.clone()),
+            Some(prev) if prev != entry => out.changed.push(entry.clone()),
+            Some(_) => {}
+        }
+    }
+    out.removed = old.keys().filter(|k| !new.contains_key(*k)).cloned().collect();
+    out
+}
+
+/// Whether `If-None-Match` matches `etag` (weak comparison, per RFC 9110 §13.1.2).
+pub fn not_modified(headers: &HeaderMap, etag: &str) -> bool {
+    let strip = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|tag| tag.trim() == "*" || strip(tag) == strip(etag))
+}
+
+/// Format a timestamp as an HTTP-date (`Last-Modified`).
+pub fn http_date(t: DateTime<Utc>) -> String {
+    t.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Respond with `304` if the client already holds `etag`, otherwise with the
+/// body, signed together with `rev`'s revision and generation time.
+pub fn respond(state: &AppState, request: &HeaderMap, rev: &BundleRevision, etag: &str, body: &Value) -> Response {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("public, max-age=3600"));
+    headers.insert(header::VARY, HeaderValue::from_static("Accept-Encoding"));
+    if let Ok(v) = HeaderValue::from_str(etag) {
+        headers.insert(header::ETAG, v);
+    }
+    if let Ok(v) = HeaderValue::from_str(&http_date(rev.last_modified)) {
+        headers.insert(header::LAST_MODIFIED, v);
+    }
+
+    if not_modified(request, etag) {
+        return (StatusCode::NOT_MODIFIED, headers).into_response();
+    }
+
+    // The signature covers the exact body bytes, so it is computed after serialisation
+    let bytes = match serde_json::to_vec(body) {
+        Ok(b) => b,
+        Err(e) => {
+            tracing::error!("bundle serialisation error: {e}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "internal error" }))).into_response();
+        }
+    };
+
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    if let Some(sig) = state.bundle_keys.sign(&bytes, rev.revision, rev.created_at.timestamp()) {
+        for (name, value) in [
+            (bundle_signing::SIGNATURE_HEADER, sig.signature),
+            (bundle_signing::KEY_ID_HEADER, sig.key_id),
+            (bundle_signing::PAYLOAD_SHA256_HEADER, sig.payload_sha256),
+            (bundle_signing::REVISION_HEADER, sig.revision.to_string()),
+            (bundle_signing::GENERATED_AT_HEADER, sig.generated_at.to_string()),
+        ] {
+            // base64url, hex and integers are always valid header values
+            if let Ok(v) = HeaderValue::from_str(&value) {
+                headers.insert(name, v);
+            }
+        }
+    }
+
+    (StatusCode::OK, headers, bytes).into_response()
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(if_none_match: &str) -> HeaderMap {
+        let mut h = HeaderMap::new();
+        h.insert(header::IF_NONE_MATCH, HeaderValue::from_str(if_none_match).unwrap());
+        h
+    }
+
+    #[test]
+    fn if_none_match_comparison() {
+        let etag = "\"abc\"";
+        assert!(not_modified(&request("\"abc\""), etag));
+        assert!(not_modified(&request("\"x\", W/\"abc\""), etag));
+        assert!(not_modified(&request("*"), etag));
+        assert!(!not_modified(&request("\"abd\""), etag));
+        assert!(!not_modified(&HeaderMap::new(), etag));
+    }
+
+    #[test]
+    fn bundle_etag_names_the_revision() {
+        let t = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let rev = BundleRevision { revision: 3, content_sha256: "abc".into(), last_modified: t, created_at: t };
+        assert_eq!(rev.etag(), "\"3-abc\"");
+        assert!(not_modified(&request("\"3-abc\""), &rev.etag()));
+
+        // The same entries again later (A → B → A) are a new revision
+        let again = BundleRevision { revision: 5, ..rev.clone() };
+        assert!(!not_modified(&request(&rev.etag()), &again.etag()));
+    }
+
+    #[test]
+    fn content_hash_depends_only_on_entries() {
+        let a = content_hash(&[json!({ "name": "a" }), json!({ "name": "b" })]).unwrap();
+        assert_eq!(a, content_hash(&[json!({ "name": "a" }), json!({ "name": "b" })]).unwrap());
+        assert_ne!(a, content_hash(&[json!({ "name": "a" })]).unwrap());
+    }
+
+    #[test]
+    fn delta_etag_names_both_revisions() {
+        let t = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let rev = BundleRevision { revision: 7, content_sha256: "abc".into(), last_modified: t, created_at: t };
+        assert_eq!(rev.delta_etag(5), "W/\"delta-5-7\"");
+        assert_ne!(rev.delta_etag(5), rev.delta_etag(6));
+
+        // A client holding the full bundle must not get a 304 for a delta, or vice versa
+        assert!(!not_modified(&request(&rev.etag()), &rev.delta_etag(5)));
+        assert!(!not_modified(&request(&rev.delta_etag(5)), &rev.etag()));
+        assert!(!not_modified(&request(&rev.delta_etag(5)), &rev.delta_etag(6)));
+    }
+
+    #[test]
+    fn delta_by_key() {
+        let old = json!([
+            { "name": "a", "pattern": "1" },
+            { "name": "b", "pattern": "2" },
+            { "name": "c", "pattern": "3" },
+        ]);
+        let new = json!([
+            { "name": "a", "pattern": "1" },
+            { "name": "b", "pattern": "2b" },
+            { "name": "d", "pattern": "4" },
+        ]);
+        let d = delta(&old, &new, "name");
+        assert_eq!(d.added, vec![json!({ "name": "d", "pattern": "4" })]);
+        assert_eq!(d.changed, vec![json!({ "name": "b", "pattern": "2b" })]);
+        assert_eq!(d.removed, vec!["c".to_string()]);
+        assert_eq!(delta(&new, &new, "name"), Delta::default());
+    }
+
+    #[test]
+    fn last_modified_is_an_http_date() {
+        let t = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        assert_eq!(http_date(t), "Tue, 14 Nov 2023 22:13:20 GMT");
+    }
+}
@@ -0,0 +1,248 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Ed25519 signatures over published bundles.
+//!
+//! Bundles are served through CDNs, so clients must not trust the transport.
+//! The registry signs the exact response body, together with the bundle
+//! revision and the time it was generated, and sends a detached signature in
+//! five headers:
+//!
+//! ```text
+//! X-Sigil-Signature       base64url(Ed25519(key,
+//!                           "sigil-registry:bundle:{revision}:{generated_at}:{payload_sha256}"))
+//! X-Sigil-Key-Id          first 16 hex chars of SHA-256(public key)
+//! X-Sigil-Payload-Sha256  hex(SHA-256(body))
+//! X-Sigil-Revision        bundle revision the body belongs to
+//! X-Sigil-Generated-At    Unix seconds at which that revision was first generated
+//! ```
+//!
+//! Signing the revision lets a client refuse a rollback (a revision older than
+//! the one it holds) from the headers alone, deltas included. `generated_at`
+//! stays the same for as long as the bundle is unchanged, so an old value does
+//! not mean a stale mirror.
+//!
+//! Current and retired public keys are published at
+//! `GET /.well-known/sigil-registry-keys`; clients should pin that list rather
+//! than fetch it through the same CDN as the bundle. [`verify`] depends only on
+//! `ed25519-dalek`, `sha2`, `base64` and `hex`, so it can be lifted into
+//! clients as is.
+//!
+//! The signing key is a base64url 32-byte Ed25519 seed in `BUNDLE_SIGNING_KEY`;
+//! `BUNDLE_RETIRED_KEYS` lists earlier public keys, comma-separated.
+
+use crate::auth;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Header carrying the base64url Ed25519 signature.
+pub const SIGNATURE_HEADER: &str = "x-sigil-signature";
+/// Header carrying the signing key's id.
+pub const KEY_ID_HEADER: &str = "x-sigil-key-id";
+/// Header carrying the hex SHA-256 of the body.
+pub const PAYLOAD_SHA256_HEADER: &str = "x-sigil-payload-sha256";
+/// Header carrying the signed bundle revision.
+pub const REVISION_HEADER: &str = "x-sigil-revision";
+/// Header carrying the signed generation time, in Unix seconds.
+pub const GENERATED_AT_HEADER: &str = "x-sigil-generated-at";
+
+/// A detached bundle signature, as carried in the `X-Sigil-*` headers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleSignature {
+    pub signature: String,
+    pub key_id: String,
+    pub payload_sha256: String,
+    pub revision: i64,
+    /// Unix seconds
+    pub generated_at: i64,
+}
+
+/// A key entry of `GET /.well-known/sigil-registry-keys`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublishedKey {
+    pub key_id: String,
+    /// Always `Ed25519`
+    pub algorithm: String,
+    /// Ed25519 public key, base64url-encoded
+    pub public_key: String,
+    /// `current` | `retired`
+    pub status: String,
+}
+
+impl PublishedKey {
+    fn new(key: &VerifyingKey, status: &str) -> Self {
+        Self {
+            key_id: key_id(key),
+            algorithm: "Ed25519".into(),
+            public_key: URL_SAFE_NO_PAD.encode(key.as_bytes()),
+            status: status.into(),
+        }
+    }
+}
+
+/// The registry's bundle signing key plus the retired keys it still publishes.
+#[derive(Clone, Default)]
+pub struct BundleKeys {
+    /// `None` serves bundles unsigned (dev mode)
+    pub current: Option<SigningKey>,
+    pub retired: Vec<VerifyingKey>,
+}
+
+impl BundleKeys {
+    /// Load from `BUNDLE_SIGNING_KEY` and `BUNDLE_RETIRED_KEYS`.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let current = match std::env::var("BUNDLE_SIGNING_KEY") {
+            Ok(seed) => {
+                let bytes: [u8; 32] = URL_SAFE_NO_PAD
+                    .decode(seed.trim())?
+                    .try_into()
+                    .map_err(|_| anyhow::anyhow!("BUNDLE_SIGNING_KEY must be a 32-byte seed"))?;
+                Some(SigningKey::from_bytes(&bytes))
+            }
+            Err(_) => None,
+        };
+
+        let retired = std::env::var("BUNDLE_RETIRED_KEYS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|k| !k.is_empty())
+            .map(|k| auth::parse_public_key(k).map_err(|e| anyhow::anyhow!("BUNDLE_RETIRED_KEYS: {e}")))
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Self { current, retired })
+    }
+
+    /// Sign `body` of `revision`, or `None` when no signing key is configured.
+    pub fn sign(&self, body: &[u8], revision: i64, generated_at: i64) -> Option<BundleSignature> {
+        self.current.as_ref().map(|key| sign(key, body, revision, generated_at))
+    }
+
+    /// Every key clients should accept, current first.
+    pub fn published(&self) -> Vec<PublishedKey> {
+        self.current
+            .iter()
+            .map(|k| PublishedKey::new(&k.verifying_key(), "current"))
+            .chain(self.retired.iter().map(|k| PublishedKey::new(k, "retired")))
+            .collect()
+    }
+}
+
+/// Short identifier of a signing key: the first 16 hex chars of SHA-256(key).
+pub fn key_id(key: &VerifyingKey) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))[..16].to_string()
+}
+
+/// The message actually signed for a body with the given digest.
+fn signed_message(revision: i64, generated_at: i64, payload_sha256: &str) -> String {
+    format!("sigil-registry:bundle:{revision}:{generated_at}:{payload_sha256}")
+}
+
+/// Produce a detached signature over `body` of `revision`, generated at
+/// `generated_at` (Unix seconds).
+pub fn sign(key: &SigningKey, body: &[u8], revision: i64, generated_at: i64) -> BundleSignature {
+    let payload_sha256 = hex::encode(Sha256::digest(body));
+    let signature = key.sign(signed_message(revision, generated_at, &payload_sha256).as_bytes());
+    BundleSignature {
+        signature: URL_SAFE_NO_PAD.encode(signature.to_bytes()),
+        key_id: key_id(&key.verifying_key()),
+        payload_sha256,
+        revision,
+        generated_at,
+    }
+}
+
+/// Verify a bundle body, with the revision and time claimed in `sig`, against
+/// its detached signature and the published keys.
+pub fn verify(body: &[u8], sig: &BundleSignature, keys: &[PublishedKey]) -> Result<(), String> {
+    let digest = hex::encode(Sha256::digest(body));
+    if digest != sig.payload_sha256 {
+        return Err("payload_sha256 does not match the body".into());
+    }
+
+    let published = keys
+        .iter()
+        .find(|k| k.key_id == sig.key_id)
+        .ok_or_else(|| format!("unknown signing key '{}'", sig.key_id))?;
+
+    let key_bytes: [u8; 32] = URL_SAFE_NO_PAD
+        .decode(&published.public_key)
+        .map_err(|e| format!("bad public key encoding: {e}"))?
+        .try_into()
+        .map_err(|_| "public key must be 32 bytes".to_string())?;
+    let key = VerifyingKey::from_bytes(&key_bytes).map_err(|e| format!("invalid public key: {e}"))?;
+    if key_id(&key) != published.key_id {
+        return Err("published key does not match its key_id".into());
+    }
+
+    let sig_bytes: [u8; 64] = URL_SAFE_NO_PAD
+        .decode(&sig.signature)
+        .map_err(|e| format!("bad signature encoding: {e}"))?
+        .try_into()
+        .map_err(|_| "signature must be 64 bytes".to_string())?;
+
+    let message = signed_message(sig.revision, sig.generated_at, &digest);
+    key.verify_strict(message.as_bytes(), &Signature::from_bytes(&sig_bytes))
+        .map_err(|_| "signature verification failed".to_string())
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> BundleKeys {
+        BundleKeys {
+            current: Some(SigningKey::from_bytes(&[3u8; 32])),
+            retired: vec![SigningKey::from_bytes(&[4u8; 32]).verifying_key()],
+        }
+    }
+
+    #[test]
+    fn signed_bundle_verifies_against_published_keys() {
+        let keys = keys();
+        let body = br#"{"version":"1","patterns":[]}"#;
+        let sig = keys.sign(body, 7, 1_700_000_000).unwrap();
+        assert_eq!(verify(body, &sig, &keys.published()), Ok(()));
+
+        // Bundles signed before a rotation still verify with the retired key
+        let old = sign(&SigningKey::from_bytes(&[4u8; 32]), body, 6, 1_690_000_000);
+        assert_eq!(verify(body, &old, &keys.published()), Ok(()));
+    }
+
+    #[test]
+    fn rejects_tampering_and_unknown_keys() {
+        let keys = keys();
+        let body = br#"{"patterns":[{"pattern":"sk_live_[0-9a-zA-Z]{24,}"}]}"#;
+        let sig = keys.sign(body, 7, 1_700_000_000).unwrap();
+
+        let tampered = br#"{"patterns":[{"pattern":"x^"}]}"#;
+        assert!(verify(tampered, &sig, &keys.published()).is_err());
+
+        // Recomputing the digest does not help without the key
+        let forged = BundleSignature { payload_sha256: hex::encode(Sha256::digest(tampered)), ..sig.clone() };
+        assert!(verify(tampered, &forged, &keys.published()).is_err());
+
+        let stranger = sign(&SigningKey::from_bytes(&[9u8; 32]), body, 7, 1_700_000_000);
+        assert!(verify(body, &stranger, &keys.published()).unwrap_err().contains("unknown signing key"));
+
+        assert!(BundleKeys::default().sign(body, 7, 1_700_000_000).is_none());
+    }
+
+    #[test]
+    fn revision_and_time_are_signed() {
+        let keys = keys();
+        let body = br#"{"version":"1","patterns":[]}"#;
+        let sig = keys.sign(body, 7, 1_700_000_000).unwrap();
+
+        // An old body replayed as a newer revision, or re-dated, fails
+        let relabelled = BundleSignature { revision: 8, ..sig.clone() };
+        assert!(verify(body, &relabelled, &keys.published()).is_err());
+        let redated = BundleSignature { generated_at: 1_800_000_000, ..sig };
+        assert!(verify(body, &redated, &keys.published()).is_err());
+    }
+}
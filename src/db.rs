@@ -4,6 +4,16 @@
 
 //! Database connection pool, Redis cache, and application state.
 
+use crate::{
+    bundle_signing::BundleKeys,
+    did,
+    pattern_overlap::OverlapCache,
+    pattern_screening::ScreeningLimits,
+    rate_limit::{RateLimiter, RateLimits},
+    replay::ReplayPolicy,
+    reputation,
+    scanner::Scanner,
+};
 use redis::aio::ConnectionManager;
 use sqlx::PgPool;
 
@@ -14,11 +24,77 @@ pub struct AppState {
     /// Redis connection manager — multiplexes a single async connection across all handlers.
     /// `None` if `REDIS_URL` is not set (registry operates without cache, just slower at scale).
     pub cache: Option<ConnectionManager>,
-    /// Optional API key for `POST /register`.
-    /// When `Some`, callers must supply the matching value in `X-Registry-Key`.
-    /// When `None`, registration is open (useful for local dev / migration).
+    /// Root API key — carries every scope and bootstraps scoped keys via
+    /// `POST /admin/keys` (see [`crate::api_keys`]).
+    /// When `None`, `POST /register` without a key is open (local dev / migration).
     /// Set via `REGISTRY_KEY` environment variable.
     pub registry_key: Option<String>,
+    /// HMAC key authenticating each head of the audit chain (see [`crate::audit`]).
+    /// `None` leaves `head_mac` empty (dev mode). Set via `AUDIT_HMAC_KEY`.
+    pub audit_key: Option<Vec<u8>>,
+    /// Ed25519 key signing served bundles, plus retired keys still published
+    /// (see [`crate::bundle_signing`]). Set via `BUNDLE_SIGNING_KEY` / `BUNDLE_RETIRED_KEYS`.
+    pub bundle_keys: BundleKeys,
+    /// Compile-size, nesting and time limits for submitted patterns
+    /// (see [`crate::pattern_screening`]). Set via `PATTERN_*` environment variables.
+    pub screening: ScreeningLimits,
+    /// The verified bundle compiled for `POST /scan`, rebuilt when its revision changes.
+    pub scanner: Scanner,
+    /// The verified patterns sampled for overlap checks, per bundle revision
+    /// (see [`crate::pattern_overlap`]).
+    pub overlaps: OverlapCache,
+    /// Origin, clock-skew window and v0 deprecation switch for signed submissions
+    /// (see [`crate::replay`]). Set via `REGISTRY_ORIGIN`, `SIGNATURE_MAX_SKEW_SECS`
+    /// and `ACCEPT_V0_SIGNATURES`.
+    pub replay: ReplayPolicy,
+    /// Reputation a DID needs to cast or change a vote (see [`crate::reputation`]).
+    /// Set via `MIN_VOTE_REPUTATION`.
+    pub min_vote_reputation: i32,
+    /// Per-IP and per-DID token buckets for the write endpoints
+    /// (see [`crate::rate_limit`]). Set via `RATE_LIMIT_*` environment variables.
+    pub rate_limiter: RateLimiter,
+    /// Whether `Fly-Client-IP` / `X-Forwarded-For` name the client, rather than
+    /// the TCP peer (see [`crate::handlers::client_ip`]). Set via `TRUST_PROXY_HEADERS`.
+    pub trust_proxy_headers: bool,
+    /// DIDs allowed to sign moderation decisions (see [`crate::handlers_moderation`]).
+    /// Empty refuses every decision. Set via `MAINTAINER_DIDS` (comma-separated).
+    pub maintainer_dids: Vec<String>,
+}
+
+/// The environment variable `name` parsed as `T`, or `None` if it is unset.
+///
+/// A value that does not parse fails startup rather than silently falling back
+/// to the default — `ACCEPT_V0_SIGNATURES=no` must not leave v0 switched on.
+pub fn env_var<T>(name: &str) -> anyhow::Result<Option<T>>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    parse_env(name, std::env::var(name).ok())
+}
+
+fn parse_env<T>(name: &str, value: Option<String>) -> anyhow::Result<Option<T>>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    value
+        .map(|v| v.trim().parse().map_err(|e| anyhow::anyhow!("{name}='{v}' is invalid: {e}")))
+        .transpose()
+}
+
+/// Parse a comma-separated list of maintainer DIDs, rejecting malformed ones.
+fn parse_maintainers(value: Option<String>) -> anyhow::Result<Vec<String>> {
+    value
+        .iter()
+        .flat_map(|v| v.split(','))
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(|d| match did::validate(d) {
+            Ok(_) => Ok(d.to_string()),
+            Err(e) => Err(anyhow::anyhow!("MAINTAINER_DIDS entry '{d}' is invalid: {e}")),
+        })
+        .collect()
 }
 
 impl AppState {
@@ -58,6 +134,82 @@ impl AppState {
             tracing::warn!("REGISTRY_KEY not set — POST /register is open (dev mode)");
         }
 
-        Ok(Self { pool, cache, registry_key })
+        let audit_key = std::env::var("AUDIT_HMAC_KEY").ok().map(String::into_bytes);
+        if audit_key.is_none() {
+            tracing::warn!("AUDIT_HMAC_KEY not set — audit chain heads are unauthenticated (dev mode)");
+        }
+
+        let bundle_keys = BundleKeys::from_env()?;
+        if bundle_keys.current.is_none() {
+            tracing::warn!("BUNDLE_SIGNING_KEY not set — bundles are served unsigned (dev mode)");
+        }
+
+        let screening = ScreeningLimits::from_env()?;
+
+        let replay = ReplayPolicy::from_env()?;
+        if std::env::var("REGISTRY_ORIGIN").is_err() {
+            tracing::warn!("REGISTRY_ORIGIN not set — v1 signatures are bound to {} (dev mode)", replay.origin);
+        }
+        if replay.accept_v0 {
+            tracing::info!("ACCEPT_V0_SIGNATURES on — unversioned submission signatures are still accepted");
+        }
+
+        let min_vote_reputation =
+            env_var("MIN_VOTE_REPUTATION")?.unwrap_or(reputation::DEFAULT_MIN_VOTE_REPUTATION);
+        if min_vote_reputation <= 0 {
+            tracing::warn!("MIN_VOTE_REPUTATION is {min_vote_reputation} — freshly registered DIDs can vote (dev mode)");
+        }
+
+        let rate_limiter = RateLimiter::new(RateLimits::from_env()?);
+
+        let trust_proxy_headers = env_var("TRUST_PROXY_HEADERS")?.unwrap_or(false);
+        if trust_proxy_headers {
+            tracing::info!("TRUST_PROXY_HEADERS on — client IPs are taken from Fly-Client-IP / X-Forwarded-For");
+        }
+
+        let maintainer_dids = parse_maintainers(std::env::var("MAINTAINER_DIDS").ok())?;
+        if maintainer_dids.is_empty() {
+            tracing::warn!("MAINTAINER_DIDS not set — moderation decisions are refused");
+        }
+
+        Ok(Self {
+            pool,
+            cache,
+            registry_key,
+            audit_key,
+            bundle_keys,
+            screening,
+            scanner: Scanner::default(),
+            overlaps: OverlapCache::default(),
+            replay,
+            min_vote_reputation,
+            rate_limiter,
+            trust_proxy_headers,
+            maintainer_dids,
+        })
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_env_values_are_errors() {
+        assert_eq!(parse_env::<bool>("X", None).unwrap(), None);
+        assert_eq!(parse_env::<bool>("X", Some("false".into())).unwrap(), Some(false));
+        assert_eq!(parse_env::<i64>("X", Some(" 300 ".into())).unwrap(), Some(300));
+        for v in ["0", "no", "False"] {
+            assert!(parse_env::<bool>("ACCEPT_V0_SIGNATURES", Some(v.into())).is_err(), "{v}");
+        }
+        assert!(parse_env::<i64>("SIGNATURE_MAX_SKEW_SECS", Some("5m".into())).is_err());
+        assert_eq!(
+            parse_maintainers(Some(" did:sigil:a_1, ,did:sigil:b ".into())).unwrap(),
+            vec!["did:sigil:a_1", "did:sigil:b"]
+        );
+        assert!(parse_maintainers(Some("did:key:z6Mk".into())).is_err());
+        assert!(parse_maintainers(None).unwrap().is_empty());
     }
 }
@@ -0,0 +1,202 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! `did:sigil` syntax.
+//!
+//! ```text
+//! did-sigil   = "did:sigil:" method-id
+//! method-id   = namespace [ "_" 1*idchar ]        ; at most 128 characters
+//! namespace   = (lower / DIGIT) *(lower / DIGIT / "_")   ; at most 32 characters
+//! idchar      = ALPHA / DIGIT / "." / "-" / "_"
+//! ```
+//!
+//! e.g. `did:sigil:parent_01` lives in namespace `parent`. Validation runs
+//! before any database or cache access, so malformed identifiers are rejected
+//! cheaply and consistently.
+//!
+//! Since namespaces may themselves contain `_`, the syntax alone is ambiguous:
+//! `did:sigil:enterprise_gateway_x` fits both `enterprise` and
+//! `enterprise_gateway`. The registry resolves this by taking the **longest**
+//! namespace already in use (see [`owning_namespace`]): registration refuses a
+//! DID filed under a shorter one, and refuses to open a namespace that would
+//! take over DIDs already filed under a shorter one.
+
+/// Method prefix every registry DID carries.
+pub const DID_PREFIX: &str = "did:sigil:";
+
+/// Maximum length of the method-specific identifier.
+pub const MAX_METHOD_ID_LEN: usize = 128;
+
+/// Maximum length of a namespace.
+pub const MAX_NAMESPACE_LEN: usize = 32;
+
+/// Why a DID or namespace was rejected.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum DidError {
+    #[error("DID must start with '{DID_PREFIX}'")]
+    MissingPrefix,
+
+    #[error("method-specific identifier is empty")]
+    EmptyId,
+
+    #[error("{what} exceeds {max} characters")]
+    TooLong { what: &'static str, max: usize },
+
+    #[error("invalid character {0:?} (allowed: A-Z a-z 0-9 . - _)")]
+    InvalidCharacter(char),
+
+    #[error("namespace {0:?} must be lowercase letters, digits and '_', starting with a letter or digit")]
+    InvalidNamespace(String),
+
+    #[error("DID is not in namespace {0:?} (expected did:sigil:{0} or did:sigil:{0}_...)")]
+    NamespaceMismatch(String),
+
+    #[error("DID falls under the more specific namespace {0:?}")]
+    ShadowedNamespace(String),
+}
+
+impl DidError {
+    /// Stable machine-readable reason, returned as `"reason"` in error bodies.
+    pub fn code(&self) -> &'static str {
+        match self {
+            DidError::MissingPrefix => "missing_prefix",
+            DidError::EmptyId => "empty_id",
+            DidError::TooLong { .. } => "too_long",
+            DidError::InvalidCharacter(_) => "invalid_character",
+            DidError::InvalidNamespace(_) => "invalid_namespace",
+            DidError::NamespaceMismatch(_) => "namespace_mismatch",
+            DidError::ShadowedNamespace(_) => "namespace_shadowed",
+        }
+    }
+}
+
+/// Validate a `did:sigil:` identifier and return its method-specific part.
+pub fn validate(did: &str) -> Result<&str, DidError> {
+    let id = did.strip_prefix(DID_PREFIX).ok_or(DidError::MissingPrefix)?;
+
+    if id.is_empty() {
+        return Err(DidError::EmptyId);
+    }
+    if id.len() > MAX_METHOD_ID_LEN {
+        return Err(DidError::TooLong { what: "method-specific identifier", max: MAX_METHOD_ID_LEN });
+    }
+    if let Some(c) = id.chars().find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))) {
+        return Err(DidError::InvalidCharacter(c));
+    }
+
+    Ok(id)
+}
+
+/// Validate a namespace name.
+pub fn validate_namespace(namespace: &str) -> Result<(), DidError> {
+    if namespace.len() > MAX_NAMESPACE_LEN {
+        return Err(DidError::TooLong { what: "namespace", max: MAX_NAMESPACE_LEN });
+    }
+
+    let valid = namespace
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        && namespace
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+
+    if !valid {
+        return Err(DidError::InvalidNamespace(namespace.to_string()));
+    }
+    Ok(())
+}
+
+/// Validate a DID together with the namespace it is being registered under.
+pub fn validate_in_namespace(did: &str, namespace: &str) -> Result<(), DidError> {
+    let id = validate(did)?;
+    validate_namespace(namespace)?;
+
+    let in_namespace = id == namespace
+        || id
+            .strip_prefix(namespace)
+            .is_some_and(|rest| rest.starts_with('_') && rest.len() > 1);
+
+    if !in_namespace {
+        return Err(DidError::NamespaceMismatch(namespace.to_string()));
+    }
+    Ok(())
+}
+
+/// Namespaces a method-specific identifier (or namespace) could sit in,
+/// longest first: `acme_eu_01` → `["acme_eu_01", "acme_eu", "acme"]`.
+///
+/// Used to find the most specific claimed namespace governing a DID.
+pub fn enclosing_namespaces(id: &str) -> Vec<&str> {
+    let mut out: Vec<&str> = id
+        .match_indices('_')
+        .map(|(i, _)| &id[..i])
+        .filter(|prefix| !prefix.is_empty())
+        .collect();
+    out.push(id);
+    out.reverse();
+    out
+}
+
+/// The namespace a method-specific identifier belongs to: the longest of the
+/// `known` namespaces enclosing it.
+pub fn owning_namespace<'a>(id: &'a str, known: &[String]) -> Option<&'a str> {
+    enclosing_namespaces(id)
+        .into_iter()
+        .find(|candidate| known.iter().any(|k| k == candidate))
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_well_formed_dids() {
+        assert_eq!(validate("did:sigil:parent_01"), Ok("parent_01"));
+        assert!(validate("did:sigil:Agent-7.v2").is_ok());
+        assert!(validate_in_namespace("did:sigil:parent_01", "parent").is_ok());
+        assert!(validate_in_namespace("did:sigil:enterprise_gateway_eu", "enterprise_gateway").is_ok());
+        assert!(validate_in_namespace("did:sigil:parent", "parent").is_ok());
+    }
+
+    #[test]
+    fn rejects_malformed_dids() {
+        assert_eq!(validate("did:key:z6Mk"), Err(DidError::MissingPrefix));
+        assert_eq!(validate("did:sigil:"), Err(DidError::EmptyId));
+        assert_eq!(validate("did:sigil:a b"), Err(DidError::InvalidCharacter(' ')));
+        assert_eq!(validate("did:sigil:a/b"), Err(DidError::InvalidCharacter('/')));
+        assert_eq!(validate("did:sigil:zoë"), Err(DidError::InvalidCharacter('ë')));
+        assert_eq!(validate(&format!("did:sigil:{}", "a".repeat(129))).unwrap_err().code(), "too_long");
+    }
+
+    #[test]
+    fn namespace_must_match_did() {
+        assert_eq!(
+            validate_in_namespace("did:sigil:parent_01", "child"),
+            Err(DidError::NamespaceMismatch("child".into()))
+        );
+        // A shared prefix is not enough — the namespace must end at '_'
+        assert!(validate_in_namespace("did:sigil:parental_01", "parent").is_err());
+        assert!(validate_in_namespace("did:sigil:parent_", "parent").is_err());
+        assert_eq!(validate_namespace("Parent").unwrap_err().code(), "invalid_namespace");
+        assert_eq!(validate_namespace("").unwrap_err().code(), "invalid_namespace");
+    }
+
+    #[test]
+    fn enclosing_namespaces_longest_first() {
+        assert_eq!(enclosing_namespaces("acme_eu_01"), vec!["acme_eu_01", "acme_eu", "acme"]);
+        assert_eq!(enclosing_namespaces("parent"), vec!["parent"]);
+    }
+
+    #[test]
+    fn longest_known_namespace_owns_the_did() {
+        let known = ["enterprise".to_string(), "enterprise_gateway".to_string()];
+        assert_eq!(owning_namespace("enterprise_gateway_x", &known), Some("enterprise_gateway"));
+        assert_eq!(owning_namespace("enterprise_x", &known), Some("enterprise"));
+        assert_eq!(owning_namespace("enterprise_gatewayx", &known), Some("enterprise"));
+        assert_eq!(owning_namespace("acme_x", &known), None);
+    }
+}
@@ -0,0 +1,150 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! W3C DID Core rendering of registry records.
+//!
+//! `GET /resolve/:did` keeps its bespoke JSON shape by default. Clients asking
+//! for `Accept: application/did+ld+json` get a DID Core document instead, and
+//! `GET /1.0/identifiers/:did` serves a full DID Resolution result compatible
+//! with the DIF Universal Resolver. A bare document cannot say that a DID is
+//! deactivated, so revoked DIDs are only ever served inside a resolution result.
+//!
+//! Keys are published as `Ed25519VerificationKey2020` with a base58btc
+//! `publicKeyMultibase` (`z` + base58(0xed 0x01 ‖ key)), the same encoding as
+//! `did:key`. The verification method fragment is the multibase key itself, so
+//! it changes on key rotation.
+
+use crate::models::ResolveResponse;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{json, Value};
+
+/// Media type of a JSON-LD DID document.
+pub const DID_LD_JSON: &str = "application/did+ld+json";
+
+/// Media type of a DID Resolution result.
+pub const DID_RESOLUTION_JSON: &str =
+    "application/ld+json;profile=\"https://w3id.org/did-resolution\"";
+
+/// Multicodec prefix for an Ed25519 public key (varint 0xed).
+const ED25519_PUB_MULTICODEC: [u8; 2] = [0xed, 0x01];
+
+/// Convert a base64url Ed25519 public key to `publicKeyMultibase` form.
+pub fn ed25519_multibase(public_key_b64: &str) -> Result<String, String> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(public_key_b64)
+        .map_err(|e| format!("bad public key encoding: {e}"))?;
+    if bytes.len() != 32 {
+        return Err("public key must be 32 bytes".into());
+    }
+
+    let mut prefixed = ED25519_PUB_MULTICODEC.to_vec();
+    prefixed.extend_from_slice(&bytes);
+    Ok(format!("z{}", bs58::encode(prefixed).into_string()))
+}
+
+/// Build the DID Core document for a resolved DID.
+pub fn did_document(resp: &ResolveResponse) -> Result<Value, String> {
+    let multibase = ed25519_multibase(&resp.public_key)?;
+    let key_id = format!("{}#{}", resp.did, multibase);
+
+    Ok(json!({
+        "@context": [
+            "https://www.w3.org/ns/did/v1",
+            "https://w3id.org/security/suites/ed25519-2020/v1",
+        ],
+        "id": resp.did,
+        "verificationMethod": [{
+            "id": key_id,
+            "type": "Ed25519VerificationKey2020",
+            "controller": resp.did,
+            "publicKeyMultibase": multibase,
+        }],
+        "authentication": [key_id],
+        "assertionMethod": [key_id],
+    }))
+}
+
+/// Build the DID Resolution `didDocumentMetadata` for a resolved DID.
+pub fn document_metadata(resp: &ResolveResponse) -> Value {
+    json!({
+        "created": xml_datetime(resp.created_at),
+        "updated": xml_datetime(resp.updated_at),
+        "deactivated": resp.status == "revoked",
+    })
+}
+
+/// Build a full DID Resolution result (`/1.0/identifiers/:did`).
+pub fn resolution_result(resp: &ResolveResponse) -> Result<Value, String> {
+    Ok(json!({
+        "@context": "https://w3id.org/did-resolution/v1",
+        "didDocument": did_document(resp)?,
+        "didResolutionMetadata": { "contentType": DID_LD_JSON },
+        "didDocumentMetadata": document_metadata(resp),
+    }))
+}
+
+/// Build a failed DID Resolution result, e.g. for `notFound` or `invalidDid`.
+pub fn resolution_error(error: &str) -> Value {
+    json!({
+        "@context": "https://w3id.org/did-resolution/v1",
+        "didDocument": null,
+        "didResolutionMetadata": { "error": error },
+        "didDocumentMetadata": {},
+    })
+}
+
+/// DID Core timestamps are XML Schema datetimes normalised to UTC, without fractions.
+fn xml_datetime(t: DateTime<Utc>) -> String {
+    t.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolved(status: &str) -> ResolveResponse {
+        let t = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        ResolveResponse {
+            did: "did:sigil:alice".into(),
+            status: status.into(),
+            public_key: URL_SAFE_NO_PAD.encode([1u8; 32]),
+            namespace: "alice".into(),
+            label: None,
+            created_at: t,
+            updated_at: t,
+            revoked_at: None,
+            previous_keys: None,
+        }
+    }
+
+    #[test]
+    fn multibase_uses_ed25519_multicodec() {
+        let mb = ed25519_multibase(&URL_SAFE_NO_PAD.encode([1u8; 32])).unwrap();
+        // Every Ed25519 did:key-style multibase key starts with z6Mk
+        assert!(mb.starts_with("z6Mk"), "{mb}");
+
+        let decoded = bs58::decode(&mb[1..]).into_vec().unwrap();
+        assert_eq!(&decoded[..2], &ED25519_PUB_MULTICODEC);
+        assert_eq!(&decoded[2..], &[1u8; 32]);
+
+        assert!(ed25519_multibase("c2hvcnQ").is_err());
+    }
+
+    #[test]
+    fn document_references_its_verification_method() {
+        let doc = did_document(&resolved("active")).unwrap();
+        let vm = &doc["verificationMethod"][0];
+        assert_eq!(doc["id"], "did:sigil:alice");
+        assert_eq!(vm["type"], "Ed25519VerificationKey2020");
+        assert_eq!(doc["authentication"][0], vm["id"]);
+        assert_eq!(doc["assertionMethod"][0], vm["id"]);
+
+        let meta = document_metadata(&resolved("revoked"));
+        assert_eq!(meta["deactivated"], true);
+        assert_eq!(meta["created"], "2023-11-14T22:13:20Z");
+    }
+}
@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Pattern bundle download accounting.
+//!
+//! Serving a bundle only appends a row to `pattern_download_events`. A
+//! background task drains the events every [`AGGREGATE_INTERVAL`] and credits
+//! each pattern in the served revision — in `pattern_downloads_daily` and in
+//! `scanner_patterns.downloads` — with one batched statement per revision and
+//! day, instead of locking every verified row on every request.
+
+use crate::bundle;
+use serde_json::Value;
+use sqlx::PgPool;
+use std::time::Duration;
+
+/// How often download events are folded into the counters.
+pub const AGGREGATE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Record a download of pattern bundle `revision` without blocking the response.
+pub fn record(pool: &PgPool, revision: i64) {
+    let pool = pool.clone();
+    tokio::spawn(async move {
+        if let Err(e) = sqlx::query("INSERT INTO pattern_download_events (revision) VALUES ($1)")
+            .bind(revision)
+            .execute(&pool)
+            .await
+        {
+            tracing::warn!("download event insert failed: {e}");
+        }
+    });
+}
+
+/// Run [`aggregate`] every [`AGGREGATE_INTERVAL`] for the life of the process.
+pub fn spawn_aggregator(pool: PgPool) {
+    tokio::spawn(async move {
+        let mut tick = tokio::time::interval(AGGREGATE_INTERVAL);
+        loop {
+            tick.tick().await;
+            match aggregate(&pool).await {
+                Ok(0) => {}
+                Ok(n) => tracing::debug!("Aggregated {n} bundle download events"),
+                Err(e) => tracing::warn!("download aggregation failed: {e}"),
+            }
+        }
+    });
+}
+
+/// Drain pending download events into the counters; returns how many were drained.
+///
+/// Runs in one transaction, so concurrent aggregators (several instances)
+/// never count an event twice.
+pub async fn aggregate(pool: &PgPool) -> Result<i64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let batches: Vec<(i64, chrono::NaiveDate, i64)> = sqlx::query_as(
+        "WITH drained AS (
+             DELETE FROM pattern_download_events
+             RETURNING revision, (downloaded_at AT TIME ZONE 'UTC')::date AS day
+         )
+         SELECT revision, day, COUNT(*) FROM drained GROUP BY revision, day",
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut drained = 0;
+    for (revision, day, count) in batches {
+        // Credit the patterns that were in the served revision; if its snapshot
+        // has been pruned, fall back to the current verified set
+        let names = bundle::snapshot(pool, "patterns", revision).await?.as_ref().map(entry_names);
+
+        sqlx::query(
+            "WITH credited AS (
+                 UPDATE scanner_patterns SET downloads = downloads + $3
+                 WHERE CASE WHEN $1::TEXT[] IS NULL
+                            THEN active = TRUE AND verified = TRUE
+                            ELSE name = ANY($1) END
+                 RETURNING id
+             )
+             INSERT INTO pattern_downloads_daily (pattern_id, day, downloads)
+             SELECT id, $2, $3 FROM credited
+             ON CONFLICT (pattern_id, day)
+             DO UPDATE SET downloads = pattern_downloads_daily.downloads + EXCLUDED.downloads",
+        )
+        .bind(names)
+        .bind(day)
+        .bind(count)
+        .execute(&mut *tx)
+        .await?;
+
+        drained += count;
+    }
+
+    tx.commit().await?;
+    Ok(drained)
+}
+
+/// Pattern names in a bundle snapshot.
+fn entry_names(entries: &Value) -> Vec<String> {
+    entries
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.get("name")?.as_str().map(str::to_string))
+        .collect()
+}
@@ -4,8 +4,9 @@
 
 //! Error types for the SIGIL Registry.
 
+use crate::did::DidError;
 use axum::{
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -21,7 +22,7 @@ pub enum RegistryError {
     Conflict(String),
 
     #[error("Invalid DID format: {0}")]
-    InvalidDid(String),
+    InvalidDid(DidError),
 
     #[error("Resource not found: {0}")]
     ResourceNotFound(String),
@@ -32,6 +33,10 @@ pub enum RegistryError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    /// The request body or one of its fields exceeds a size limit.
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+
     #[error("Invalid signature: {0}")]
     InvalidSignature(String),
 
@@ -41,13 +46,21 @@ pub enum RegistryError {
     #[error("Already voted")]
     AlreadyVoted,
 
-    #[error("Invalid vote: must be 'up' or 'down'")]
+    #[error("Invalid vote: must be 'up', 'down' or 'retract'")]
     InvalidVote,
 
+    /// The caller is authenticated but not allowed to perform this action.
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
     /// Returned when a request is missing or supplies a wrong `X-Registry-Key`.
     #[error("Unauthorized: invalid or missing registry API key")]
     Unauthorized,
 
+    /// A rate limit is exhausted; answered with `429` and `Retry-After`.
+    #[error("Rate limited: retry in {retry_after_secs}s")]
+    RateLimited { retry_after_secs: u64 },
+
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
 
@@ -65,12 +78,13 @@ impl IntoResponse for RegistryError {
                 StatusCode::CONFLICT,
                 format!("DID already registered: {did}"),
             ),
-            RegistryError::InvalidDid(msg) => {
-                (StatusCode::BAD_REQUEST, format!("Invalid DID: {msg}"))
+            RegistryError::InvalidDid(e) => {
+                (StatusCode::BAD_REQUEST, format!("Invalid DID: {e}"))
             }
             RegistryError::ResourceNotFound(msg) => (StatusCode::NOT_FOUND, msg.clone()),
             RegistryError::Duplicate(msg) => (StatusCode::CONFLICT, msg.clone()),
             RegistryError::Validation(msg) => (StatusCode::UNPROCESSABLE_ENTITY, msg.clone()),
+            RegistryError::PayloadTooLarge(msg) => (StatusCode::PAYLOAD_TOO_LARGE, msg.clone()),
             RegistryError::InvalidSignature(msg) => (
                 StatusCode::UNAUTHORIZED,
                 format!("Invalid signature: {msg}"),
@@ -85,12 +99,19 @@ impl IntoResponse for RegistryError {
             ),
             RegistryError::InvalidVote => (
                 StatusCode::BAD_REQUEST,
-                "Vote must be 'up' or 'down'".into(),
+                "Vote must be 'up', 'down' or 'retract'".into(),
             ),
+            RegistryError::Forbidden(msg) => {
+                (StatusCode::FORBIDDEN, format!("Forbidden: {msg}"))
+            }
             RegistryError::Unauthorized => (
                 StatusCode::UNAUTHORIZED,
                 "Unauthorized: invalid or missing X-Registry-Key header".into(),
             ),
+            RegistryError::RateLimited { retry_after_secs } => (
+                StatusCode::TOO_MANY_REQUESTS,
+                format!("Too many requests; retry in {retry_after_secs}s"),
+            ),
             RegistryError::Database(e) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 format!("Database error: {e}"),
@@ -101,6 +122,18 @@ impl IntoResponse for RegistryError {
             ),
         };
 
-        (status, Json(json!({ "error": message }))).into_response()
+        match &self {
+            // Structured reason so clients can branch without parsing the message
+            RegistryError::InvalidDid(e) => {
+                (status, Json(json!({ "error": message, "reason": e.code() }))).into_response()
+            }
+            RegistryError::RateLimited { retry_after_secs } => (
+                status,
+                [(header::RETRY_AFTER, retry_after_secs.to_string())],
+                Json(json!({ "error": message, "retry_after": retry_after_secs })),
+            )
+                .into_response(),
+            _ => (status, Json(json!({ "error": message }))).into_response(),
+        }
     }
 }
@@ -5,22 +5,36 @@
 //! Axum route handlers for the SIGIL Registry.
 
 use crate::{
+    api_keys::{self, Principal, Scope},
+    audit, auth,
     db::AppState,
+    did, did_document,
     error::RegistryError,
-    models::{RegisterRequest, ResolveResponse},
+    handlers_namespaces,
+    models::{
+        DidEvent, KeyHistoryEntry, LabelRequest, RegisterRequest, ResolveQuery, ResolveResponse,
+        RevokeRequest, RotateKeyRequest,
+    },
+    replay, reputation,
 };
 use axum::{
-    extract::{Path, State},
-    http::{HeaderMap, StatusCode},
+    extract::{ConnectInfo, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 use redis::AsyncCommands;
 use serde_json::{json, Value};
-use std::sync::Arc;
+use sqlx::PgConnection;
+use std::{net::SocketAddr, sync::Arc};
+use uuid::Uuid;
 
 /// Cache TTL for DID documents: 5 minutes.
 const DID_CACHE_TTL_SECS: u64 = 300;
 
+/// Lifetime of a registration nonce: 5 minutes.
+const REGISTRATION_NONCE_TTL_SECS: i64 = 300;
+
 // ── Health ────────────────────────────────────────────────────────────────────
 
 /// `GET /health` — Health check
@@ -32,27 +46,129 @@ pub async fn health() -> Json<Value> {
     }))
 }
 
+// ── Well-known ────────────────────────────────────────────────────────────────
+
+/// `GET /.well-known/sigil-registry-keys` — Public keys that sign served bundles.
+///
+/// Lists the current key and every retired key still accepted, so clients can
+/// verify bundles signed before a rotation.
+pub async fn registry_keys(State(state): State<Arc<AppState>>) -> Json<Value> {
+    Json(json!({ "keys": state.bundle_keys.published() }))
+}
+
 // ── Resolve ───────────────────────────────────────────────────────────────────
 
 /// `GET /resolve/:did` — Resolve a DID to its public key and metadata.
 ///
 /// Cache-aside: check Redis first (5-min TTL), fall through to PostgreSQL on miss.
 /// Per SIGIL Spec §7.2: revoked DIDs return `"status": "revoked"`.
+///
+/// With `?include_history=true` the response also lists retired keys and their
+/// validity windows (see `POST /rotate/:did`); these lookups bypass the cache.
+///
+/// Content negotiation: `Accept: application/did+ld+json` returns a W3C DID Core
+/// document instead of the registry's own JSON shape (see [`did_document`]). A
+/// revoked DID has no usable document: it answers `410 Gone` with the DID
+/// Resolution result of `/1.0/identifiers`, whose `didDocumentMetadata` marks
+/// it `deactivated`.
 pub async fn resolve_did(
     State(state): State<Arc<AppState>>,
     Path(did): Path<String>,
-) -> Result<Json<ResolveResponse>, RegistryError> {
+    Query(q): Query<ResolveQuery>,
+    headers: HeaderMap,
+) -> Result<Response, RegistryError> {
+    let resp = lookup_did(&state, &did, q.include_history.unwrap_or(false)).await?;
+
+    let wants_did_document = headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|accept| accept.contains(did_document::DID_LD_JSON));
+
+    if wants_did_document && resp.status == "revoked" {
+        let result = did_document::resolution_result(&resp)
+            .map_err(|e| anyhow::anyhow!("stored key for {did} is unusable: {e}"))?;
+        return Ok((
+            StatusCode::GONE,
+            [
+                (header::CONTENT_TYPE, did_document::DID_RESOLUTION_JSON),
+                (header::VARY, "Accept"),
+            ],
+            Json(result),
+        )
+            .into_response());
+    }
+
+    if wants_did_document {
+        let doc = did_document::did_document(&resp)
+            .map_err(|e| anyhow::anyhow!("stored key for {did} is unusable: {e}"))?;
+        return Ok((
+            [
+                (header::CONTENT_TYPE, did_document::DID_LD_JSON),
+                (header::VARY, "Accept"),
+            ],
+            Json(doc),
+        )
+            .into_response());
+    }
+
+    Ok(([(header::VARY, "Accept")], Json(resp)).into_response())
+}
+
+/// `GET /1.0/identifiers/:did` — DIF Universal Resolver-compatible DID Resolution.
+///
+/// Returns `{ didDocument, didResolutionMetadata, didDocumentMetadata }`; failures
+/// are reported through `didResolutionMetadata.error` as the resolution spec requires.
+pub async fn resolve_identifier(
+    State(state): State<Arc<AppState>>,
+    Path(did): Path<String>,
+) -> Response {
+    let content_type = [(header::CONTENT_TYPE, did_document::DID_RESOLUTION_JSON)];
+
+    let result = lookup_did(&state, &did, false).await.and_then(|resp| {
+        did_document::resolution_result(&resp).map_err(|e| {
+            RegistryError::Internal(anyhow::anyhow!("stored key for {did} is unusable: {e}"))
+        })
+    });
+
+    match result {
+        Ok(body) => (StatusCode::OK, content_type, Json(body)).into_response(),
+        Err(RegistryError::NotFound(_)) => (
+            StatusCode::NOT_FOUND,
+            content_type,
+            Json(did_document::resolution_error("notFound")),
+        )
+            .into_response(),
+        Err(RegistryError::InvalidDid(_)) => (
+            StatusCode::BAD_REQUEST,
+            content_type,
+            Json(did_document::resolution_error("invalidDid")),
+        )
+            .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Cache-aside lookup of a DID record shared by the resolve endpoints.
+///
+/// The DID is syntax-checked first, so malformed lookups never reach Redis or PostgreSQL.
+async fn lookup_did(
+    state: &AppState,
+    did: &str,
+    include_history: bool,
+) -> Result<ResolveResponse, RegistryError> {
+    did::validate(did).map_err(RegistryError::InvalidDid)?;
+
     let cache_key = format!("did:{}", did);
 
     // ── Cache read ────────────────────────────────────────────────────────────
-    if let Some(mut cache) = state.cache.clone() {
+    if let Some(mut cache) = state.cache.clone().filter(|_| !include_history) {
         match cache.get::<_, Option<String>>(&cache_key).await {
             Ok(Some(cached)) => {
                 // Cache hit — deserialize and return
                 match serde_json::from_str::<ResolveResponse>(&cached) {
                     Ok(resp) => {
                         tracing::debug!("DID cache HIT: {}", did);
-                        return Ok(Json(resp));
+                        return Ok(resp);
                     }
                     Err(e) => {
                         // Corrupted cache entry — log and fall through to DB
@@ -75,17 +191,29 @@ pub async fn resolve_did(
         "SELECT did, public_key, namespace, label, status, created_at, updated_at, revoked_at
          FROM dids WHERE did = $1",
     )
-    .bind(&did)
+    .bind(did)
     .fetch_optional(&state.pool)
     .await?
-    .ok_or_else(|| RegistryError::NotFound(did.clone()))?;
+    .ok_or_else(|| RegistryError::NotFound(did.to_string()))?;
+
+    let mut resp: ResolveResponse = row.into();
 
-    let resp: ResolveResponse = row.into();
+    if include_history {
+        let previous = sqlx::query_as::<_, KeyHistoryEntry>(
+            "SELECT public_key, valid_from, valid_until
+             FROM did_key_history WHERE did = $1
+             ORDER BY valid_until DESC",
+        )
+        .bind(did)
+        .fetch_all(&state.pool)
+        .await?;
+        resp.previous_keys = Some(previous);
+    }
 
     // ── Cache write ───────────────────────────────────────────────────────────
     // Only cache active DIDs. Revoked DIDs have a short natural TTL from the
     // 24-hour spec window — don't cache them to allow timely revocation propagation.
-    if resp.status == "active" {
+    if resp.status == "active" && !include_history {
         if let Some(mut cache) = state.cache.clone() {
             if let Ok(serialized) = serde_json::to_string(&resp) {
                 if let Err(e) = cache
@@ -99,44 +227,82 @@ pub async fn resolve_did(
         }
     }
 
-    Ok(Json(resp))
+    Ok(resp)
 }
 
 // ── Register ─────────────────────────────────────────────────────────────────────────────
 
+/// `GET /register/nonce` — Issue a single-use nonce for a registration proof.
+///
+/// The nonce expires after 5 minutes and is consumed by the first `POST /register`
+/// that presents it. Each call is charged to the client IP's registration
+/// limit (see [`crate::rate_limit`]).
+pub async fn registration_nonce(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Value>, RegistryError> {
+    let nonce = Uuid::new_v4().simple().to_string();
+    let expires_at = chrono::Utc::now() + chrono::Duration::seconds(REGISTRATION_NONCE_TTL_SECS);
+
+    // Opportunistic cleanup keeps the table small without a background job
+    sqlx::query("DELETE FROM registration_nonces WHERE expires_at < NOW()")
+        .execute(&state.pool)
+        .await?;
+
+    sqlx::query("INSERT INTO registration_nonces (nonce, expires_at) VALUES ($1, $2)")
+        .bind(&nonce)
+        .bind(expires_at)
+        .execute(&state.pool)
+        .await?;
+
+    Ok(Json(json!({ "nonce": nonce, "expires_at": expires_at })))
+}
+
 /// `POST /register` — Register a new DID.
 ///
-/// Body: `{ "did": "did:sigil:foo", "public_key": "<base64url>", "namespace": "foo", "label": "..." }`
+/// Body: `{ "did": "did:sigil:foo", "public_key": "<base64url>", "namespace": "foo",
+///          "label": "...", "nonce": "<from GET /register/nonce>", "signature": "<base64url>" }`
+///
+/// Gap 1: When `REGISTRY_KEY` env var is set, callers must supply an API key with
+/// the `register` scope (and, for namespace-restricted keys, the DID's namespace)
+/// in the `X-Registry-Key` request header. See [`api_keys`].
 ///
-/// Gap 1: When `REGISTRY_KEY` env var is set, callers must supply the matching value
-/// in the `X-Registry-Key` request header. Comparison is constant-time to prevent
-/// timing-based key enumeration attacks.
+/// The public key must be a valid, non-weak Ed25519 point, and `signature` must
+/// prove possession of its private key by signing
+/// `sigil-registry:register:{did}:{public_key}:{nonce}`.
 pub async fn register_did(
     State(state): State<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
     headers: HeaderMap,
     Json(req): Json<RegisterRequest>,
 ) -> Result<(StatusCode, Json<Value>), RegistryError> {
     // ── API key gate (────────────────────────────────────────────────────────────────
-    if let Some(expected_key) = &state.registry_key {
-        let supplied = headers
-            .get("x-registry-key")
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("");
-
-        // Constant-time comparison to resist timing attacks
-        if !constant_time_eq(supplied.as_bytes(), expected_key.as_bytes()) {
-            tracing::warn!("Rejected /register: invalid or missing X-Registry-Key");
+    let principal = api_keys::authenticate(&state, &headers).await?;
+    match &principal {
+        Some(p) => {
+            p.require(Scope::Register)?;
+            p.require_namespace(&req.namespace)?;
+        }
+        None if state.registry_key.is_some() => {
+            tracing::warn!("Rejected /register: missing X-Registry-Key");
             return Err(RegistryError::Unauthorized);
         }
+        None => {} // dev mode: registration is open
     }
 
     // ── Validate DID format ───────────────────────────────────────────────────────────────
-    if !req.did.starts_with("did:sigil:") {
-        return Err(RegistryError::InvalidDid(format!(
-            "DID must start with 'did:sigil:' — got: {}",
-            req.did
-        )));
-    }
+    did::validate_in_namespace(&req.did, &req.namespace).map_err(RegistryError::InvalidDid)?;
+
+    // ── Namespace ownership ───────────────────────────────────────────────────────────────
+    handlers_namespaces::check_most_specific(&state.pool, &req).await?;
+    handlers_namespaces::authorize_registration(&state.pool, &req).await?;
+
+    // ── Validate key + proof of possession ────────────────────────────────────────────────
+    auth::parse_public_key(&req.public_key)
+        .map_err(|e| RegistryError::Validation(format!("invalid public_key: {e}")))?;
+
+    let message = auth::register_message(&req.did, &req.public_key, &req.nonce);
+    auth::verify_signature(&req.public_key, &message, &req.signature)
+        .map_err(RegistryError::InvalidSignature)?;
 
     // Check for duplicates
     let exists: bool = sqlx::query_scalar::<_, bool>(
@@ -150,7 +316,20 @@ pub async fn register_did(
         return Err(RegistryError::Conflict(req.did));
     }
 
-    // Insert the new DID
+    // Consume the nonce, insert the new DID and its audit event atomically
+    let mut tx = state.pool.begin().await?;
+
+    let consumed = sqlx::query(
+        "DELETE FROM registration_nonces WHERE nonce = $1 AND expires_at > NOW()",
+    )
+    .bind(&req.nonce)
+    .execute(&mut *tx)
+    .await?;
+
+    if consumed.rows_affected() == 0 {
+        return Err(RegistryError::InvalidSignature("unknown or expired nonce".into()));
+    }
+
     sqlx::query(
         "INSERT INTO dids (did, public_key, namespace, label, status)
          VALUES ($1, $2, $3, $4, 'active')",
@@ -159,9 +338,21 @@ pub async fn register_did(
     .bind(&req.public_key)
     .bind(&req.namespace)
     .bind(&req.label)
-    .execute(&state.pool)
+    .execute(&mut *tx)
+    .await?;
+
+    record_event(
+        &state,
+        &mut tx,
+        &req.did,
+        "registered",
+        &request_actor(&state, principal.as_ref(), &headers, &peer),
+        json!({ "public_key": req.public_key, "namespace": req.namespace, "label": req.label }),
+    )
     .await?;
 
+    tx.commit().await?;
+
     tracing::info!("Registered new DID: {}", req.did);
 
     Ok((
@@ -174,60 +365,458 @@ pub async fn register_did(
     ))
 }
 
-/// Constant-time byte slice comparison.
+/// Authorise a change to `did`: either an API key with `scope` (allowed in the
+/// DID's namespace), or a `(timestamp, signature)` proof by the DID's current
+/// key over `message(timestamp)`. A `nonce` signed into the message is spent
+/// once the signature verified (see [`replay::use_nonce`]).
 ///
-/// Returns `true` only if both slices are equal AND of the same length.
-/// Always iterates the full length of the longer slice to prevent timing leaks.
-fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
-    if a.len() != b.len() {
-        return false;
+/// Returns the API key principal when one was used.
+async fn authorize_did_owner(
+    state: &AppState,
+    headers: &HeaderMap,
+    did: &str,
+    scope: Scope,
+    proof: Option<(i64, &str)>,
+    nonce: Option<&str>,
+    message: impl FnOnce(i64) -> String,
+) -> Result<Option<Principal>, RegistryError> {
+    if let Some(principal) = api_keys::authenticate(state, headers).await? {
+        principal.require(scope)?;
+
+        let namespace: String = sqlx::query_scalar("SELECT namespace FROM dids WHERE did = $1")
+            .bind(did)
+            .fetch_optional(&state.pool)
+            .await?
+            .ok_or_else(|| RegistryError::NotFound(did.to_string()))?;
+        principal.require_namespace(&namespace)?;
+
+        return Ok(Some(principal));
+    }
+
+    let Some((timestamp, signature)) = proof else {
+        tracing::warn!("Rejected change to {}: no operator key or signed body", did);
+        return Err(RegistryError::Unauthorized);
+    };
+
+    auth::check_timestamp(timestamp, chrono::Utc::now().timestamp(), state.replay.max_skew_secs)
+        .map_err(RegistryError::InvalidSignature)?;
+
+    let public_key: String = sqlx::query_scalar(
+        "SELECT public_key FROM dids WHERE did = $1 AND status = 'active'",
+    )
+    .bind(did)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| RegistryError::NotFound(did.to_string()))?;
+
+    auth::verify_signature(&public_key, &message(timestamp), signature)
+        .map_err(RegistryError::InvalidSignature)?;
+
+    if let Some(nonce) = nonce {
+        replay::use_nonce(state, did, nonce).await?;
     }
-    a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+
+    Ok(None)
+}
+
+// ── DID audit events ──────────────────────────────────────────────────────────
+
+/// The client IP: the TCP peer address, or with `trust_proxy_headers` Fly's
+/// `Fly-Client-IP`, then the last hop of `X-Forwarded-For`.
+///
+/// The headers are only trustworthy behind a proxy that sets them (the Fly
+/// edge overwrites `Fly-Client-IP` and appends to `X-Forwarded-For`); earlier
+/// `X-Forwarded-For` hops are whatever the client sent.
+pub(crate) fn client_ip(headers: &HeaderMap, peer: &SocketAddr, trust_proxy_headers: bool) -> String {
+    if !trust_proxy_headers {
+        return peer.ip().to_string();
+    }
+    headers
+        .get("fly-client-ip")
+        .and_then(|v| v.to_str().ok())
+        .or_else(|| {
+            headers
+                .get("x-forwarded-for")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.rsplit(',').next())
+        })
+        .map(|ip| ip.trim().to_string())
+        .filter(|ip| !ip.is_empty())
+        .unwrap_or_else(|| peer.ip().to_string())
+}
+
+/// The `did_events.actor` value for a request: the API key used, else the client IP.
+fn request_actor(state: &AppState, principal: Option<&Principal>, headers: &HeaderMap, peer: &SocketAddr) -> String {
+    match principal {
+        Some(p) => p.actor(),
+        None => format!("ip:{}", client_ip(headers, peer, state.trust_proxy_headers)),
+    }
+}
+
+/// Append a lifecycle event to `did_events` and to the tamper-evident audit
+/// chain (as action `did.{event_type}`).
+///
+/// Takes a connection rather than the pool so callers record the event inside
+/// the same transaction as the `dids` mutation it describes.
+async fn record_event(
+    state: &AppState,
+    conn: &mut PgConnection,
+    did: &str,
+    event_type: &str,
+    actor: &str,
+    metadata: Value,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO did_events (did, event_type, actor, metadata)
+         VALUES ($1, $2, $3, $4)",
+    )
+    .bind(did)
+    .bind(event_type)
+    .bind(actor)
+    .bind(&metadata)
+    .execute(&mut *conn)
+    .await?;
+
+    audit::append(
+        conn,
+        state.audit_key.as_deref(),
+        &format!("did.{event_type}"),
+        did,
+        Some(actor),
+        metadata,
+    )
+    .await?;
+    Ok(())
+}
+
+/// `GET /resolve/:did/history` — The DID's lifecycle events, oldest first.
+///
+/// Client IPs in `actor` are redacted unless the caller holds an API key with
+/// the `read-private` scope.
+pub async fn did_history(
+    State(state): State<Arc<AppState>>,
+    Path(did): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, RegistryError> {
+    did::validate(&did).map_err(RegistryError::InvalidDid)?;
+
+    let can_read_private = api_keys::authenticate(&state, &headers)
+        .await?
+        .is_some_and(|p| p.has(Scope::ReadPrivate));
+
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM dids WHERE did = $1)")
+        .bind(&did)
+        .fetch_one(&state.pool)
+        .await?;
+
+    if !exists {
+        return Err(RegistryError::NotFound(did));
+    }
+
+    let mut events = sqlx::query_as::<_, DidEvent>(
+        "SELECT id, event_type, actor, occurred_at, metadata
+         FROM did_events WHERE did = $1
+         ORDER BY occurred_at, id",
+    )
+    .bind(&did)
+    .fetch_all(&state.pool)
+    .await?;
+
+    if !can_read_private {
+        for event in &mut events {
+            if event.actor.as_deref().is_some_and(|a| a.starts_with("ip:")) {
+                event.actor = Some("ip:redacted".into());
+            }
+        }
+    }
+
+    Ok(Json(json!({
+        "did": did,
+        "count": events.len(),
+        "events": events,
+    })))
+}
+
+/// `GET /resolve/:did/reputation` — The DID's reputation and its components.
+///
+/// The points are the weight the DID's next vote would carry; `can_vote`
+/// compares them with `MIN_VOTE_REPUTATION` (see [`reputation::Reputation::can_vote`]).
+pub async fn did_reputation(
+    State(state): State<Arc<AppState>>,
+    Path(did): Path<String>,
+) -> Result<Json<Value>, RegistryError> {
+    did::validate(&did).map_err(RegistryError::InvalidDid)?;
+
+    let mut conn = state.pool.acquire().await?;
+    let reputation = reputation::of(&mut conn, &did)
+        .await?
+        .ok_or_else(|| RegistryError::NotFound(did.clone()))?;
+
+    Ok(Json(json!({
+        "did": did,
+        "reputation": reputation,
+        "min_vote_reputation": state.min_vote_reputation,
+        "can_vote": reputation.can_vote(state.min_vote_reputation),
+    })))
 }
 
 // ── Revoke ────────────────────────────────────────────────────────────────────
 
 /// `POST /revoke/:did` — Revoke a DID.
 ///
+/// Body: `{ "timestamp": 1700000000, "signature": "<base64url>" }`
+///
+/// The caller must either present an API key with the `revoke` scope, or sign
+/// `sigil-registry:revoke:{did}:{timestamp}` with the DID's own key. The
+/// timestamp must lie within [`auth::TIMESTAMP_WINDOW_SECS`] of the server
+/// clock, so a captured request cannot be replayed later (replays inside the
+/// window are harmless — revocation is one-way).
+///
 /// Invalidates the Redis cache entry immediately so verifiers see the revocation
 /// within the next request (no need to wait for TTL expiry).
 pub async fn revoke_did(
     State(state): State<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
     Path(did): Path<String>,
+    headers: HeaderMap,
+    body: Option<Json<RevokeRequest>>,
 ) -> Result<Json<Value>, RegistryError> {
+    did::validate(&did).map_err(RegistryError::InvalidDid)?;
+
+    // ── Authorization: operator key or self-signed request ────────────────────
+    let proof = body.as_ref().map(|Json(r)| (r.timestamp, r.signature.as_str()));
+    let principal = authorize_did_owner(&state, &headers, &did, Scope::Revoke, proof, None, |ts| {
+        auth::revoke_message(&did, ts)
+    })
+    .await?;
+
+    let mut tx = state.pool.begin().await?;
+
     let result = sqlx::query(
         "UPDATE dids
          SET status = 'revoked', revoked_at = NOW(), updated_at = NOW()
          WHERE did = $1 AND status = 'active'",
     )
     .bind(&did)
-    .execute(&state.pool)
+    .execute(&mut *tx)
     .await?;
 
     if result.rows_affected() == 0 {
         return Err(RegistryError::NotFound(did));
     }
 
+    record_event(
+        &state,
+        &mut tx,
+        &did,
+        "revoked",
+        &request_actor(&state, principal.as_ref(), &headers, &peer),
+        json!({ "authorized_by": if principal.is_some() { "api_key" } else { "self" } }),
+    )
+    .await?;
+
+    tx.commit().await?;
+
     tracing::warn!("Revoked DID: {}", did);
 
     // ── Cache invalidation on revoke ──────────────────────────────────────────
     // Delete immediately — don't wait for TTL. Revocation must propagate fast.
+    invalidate_cached_did(&state, &did).await;
+
+    Ok(Json(json!({
+        "did": did,
+        "status": "revoked",
+        "message": "DID revoked. Key remains resolvable for 24h per SIGIL Spec §11.3.",
+    })))
+}
+
+/// Drop the cached `did:{did}` entry so the next resolve reads from PostgreSQL.
+async fn invalidate_cached_did(state: &AppState, did: &str) {
     if let Some(mut cache) = state.cache.clone() {
         let cache_key = format!("did:{}", did);
         if let Err(e) = cache.del::<_, ()>(&cache_key).await {
-            tracing::warn!("Redis DEL failed on revoke (cache may be stale for up to 5m): {}", e);
+            tracing::warn!("Redis DEL failed for {} (cache may be stale for up to 5m): {}", did, e);
         } else {
-            tracing::info!("Cache invalidated for revoked DID: {}", did);
+            tracing::info!("Cache invalidated for DID: {}", did);
         }
     }
+}
+
+// ── Rotate ────────────────────────────────────────────────────────────────────
+
+/// `POST /rotate/:did` — Replace a DID's public key.
+///
+/// Body: `{ "new_public_key": "<base64url>", "timestamp": 1700000000, "nonce": "...",
+///          "signature": "<by current key>", "new_key_signature": "<by new key>" }`
+///
+/// Both keys must sign `sigil-registry:rotate:{did}:{new_public_key}:{timestamp}:{nonce}`,
+/// giving a continuity proof from the old key to the new one. The nonce is
+/// single-use, so a captured rotation cannot be replayed after a later one. The retired key
+/// is kept in `did_key_history` with its validity window so receipts signed
+/// before the rotation remain verifiable.
+pub async fn rotate_key(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    Path(did): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<RotateKeyRequest>,
+) -> Result<Json<Value>, RegistryError> {
+    did::validate(&did).map_err(RegistryError::InvalidDid)?;
+
+    auth::check_timestamp(req.timestamp, chrono::Utc::now().timestamp(), state.replay.max_skew_secs)
+        .map_err(RegistryError::InvalidSignature)?;
+
+    let mut tx = state.pool.begin().await?;
+
+    // Lock the row so concurrent rotations can't both succeed against the same key
+    let current: Option<(String, chrono::DateTime<chrono::Utc>)> = sqlx::query_as(
+        "SELECT public_key, created_at FROM dids
+         WHERE did = $1 AND status = 'active'
+         FOR UPDATE",
+    )
+    .bind(&did)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let (current_key, created_at) = current.ok_or_else(|| RegistryError::NotFound(did.clone()))?;
+
+    verify_rotation(&did, &current_key, &req)?;
+    replay::use_nonce(&state, &did, &req.nonce).await?;
+
+    let previous_rotation: Option<chrono::DateTime<chrono::Utc>> =
+        sqlx::query_scalar("SELECT MAX(valid_until) FROM did_key_history WHERE did = $1")
+            .bind(&did)
+            .fetch_one(&mut *tx)
+            .await?;
+
+    sqlx::query(
+        "INSERT INTO did_key_history (did, public_key, valid_from, valid_until)
+         VALUES ($1, $2, $3, NOW())",
+    )
+    .bind(&did)
+    .bind(&current_key)
+    .bind(retired_key_valid_from(previous_rotation, created_at))
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("UPDATE dids SET public_key = $2, updated_at = NOW() WHERE did = $1")
+        .bind(&did)
+        .bind(&req.new_public_key)
+        .execute(&mut *tx)
+        .await?;
+
+    record_event(
+        &state,
+        &mut tx,
+        &did,
+        "key_rotated",
+        &request_actor(&state, None, &headers, &peer),
+        json!({ "previous_key": current_key, "new_key": req.new_public_key }),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    tracing::warn!("Rotated key for DID: {}", did);
+
+    invalidate_cached_did(&state, &did).await;
 
     Ok(Json(json!({
         "did": did,
-        "status": "revoked",
-        "message": "DID revoked. Key remains resolvable for 24h per SIGIL Spec §11.3.",
+        "public_key": req.new_public_key,
+        "message": "Key rotated. The previous key remains listed via ?include_history=true.",
     })))
 }
 
+/// Check the continuity proof of a rotation: a different key, and the rotate
+/// message signed by both the current and the new one.
+fn verify_rotation(did: &str, current_key: &str, req: &RotateKeyRequest) -> Result<(), RegistryError> {
+    if req.new_public_key == current_key {
+        return Err(RegistryError::Validation(
+            "new_public_key must differ from the current key".into(),
+        ));
+    }
+
+    let message = auth::rotate_message(did, &req.new_public_key, req.timestamp, &req.nonce);
+    auth::verify_signature(current_key, &message, &req.signature)
+        .map_err(|e| RegistryError::InvalidSignature(format!("current key: {e}")))?;
+    auth::verify_signature(&req.new_public_key, &message, &req.new_key_signature)
+        .map_err(|e| RegistryError::InvalidSignature(format!("new key: {e}")))
+}
+
+/// When an outgoing key became valid: at the previous rotation, or at
+/// registration if it is the first key.
+fn retired_key_valid_from(
+    previous_rotation: Option<chrono::DateTime<chrono::Utc>>,
+    registered_at: chrono::DateTime<chrono::Utc>,
+) -> chrono::DateTime<chrono::Utc> {
+    previous_rotation.unwrap_or(registered_at)
+}
+
+// ── Label ─────────────────────────────────────────────────────────────────────
+
+/// `POST /label/:did` — Change (or clear) a DID's human-readable label.
+///
+/// Body: `{ "label": "...", "timestamp": 1700000000, "nonce": "...", "signature": "<base64url>" }`
+///
+/// Authorised like `POST /revoke/:did`: an API key with the `register` scope, or
+/// the DID's own key signing `sigil-registry:label:{did}:{label}:{timestamp}:{nonce}`.
+/// The nonce is single-use, so a captured change cannot be replayed to undo a
+/// later one.
+pub async fn update_label(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    Path(did): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<LabelRequest>,
+) -> Result<Json<Value>, RegistryError> {
+    did::validate(&did).map_err(RegistryError::InvalidDid)?;
+
+    let label = req.label.as_deref().unwrap_or("");
+    let proof = req.timestamp.zip(req.signature.as_deref());
+    let nonce = match (proof, req.nonce.as_deref()) {
+        (Some(_), None) => return Err(RegistryError::Validation("a signed label change requires a nonce".into())),
+        (_, nonce) => nonce,
+    };
+    let principal = authorize_did_owner(&state, &headers, &did, Scope::Register, proof, nonce, |ts| {
+        auth::label_message(&did, label, ts, nonce.unwrap_or(""))
+    })
+    .await?;
+
+    let mut tx = state.pool.begin().await?;
+
+    let previous: Option<Option<String>> = sqlx::query_scalar(
+        "SELECT label FROM dids WHERE did = $1 AND status = 'active' FOR UPDATE",
+    )
+    .bind(&did)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let previous = previous.ok_or_else(|| RegistryError::NotFound(did.clone()))?;
+
+    sqlx::query("UPDATE dids SET label = $2, updated_at = NOW() WHERE did = $1")
+        .bind(&did)
+        .bind(&req.label)
+        .execute(&mut *tx)
+        .await?;
+
+    record_event(
+        &state,
+        &mut tx,
+        &did,
+        "label_changed",
+        &request_actor(&state, principal.as_ref(), &headers, &peer),
+        json!({ "previous_label": previous, "label": req.label }),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    invalidate_cached_did(&state, &did).await;
+
+    Ok(Json(json!({ "did": did, "label": req.label })))
+}
+
 // ── Tests ─────────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -240,4 +829,66 @@ mod tests {
         assert_eq!(resp.0["status"], "ok");
         assert_eq!(resp.0["service"], "sigil-registry");
     }
+
+    #[test]
+    fn client_ip_trusts_proxy_headers_only_when_told() {
+        let peer: SocketAddr = "10.0.0.1:4000".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        assert_eq!(client_ip(&headers, &peer, true), "10.0.0.1");
+
+        // The first hop is whatever the client sent; the proxy appends the last
+        headers.insert("x-forwarded-for", "203.0.113.7, 10.0.0.2".parse().unwrap());
+        assert_eq!(client_ip(&headers, &peer, true), "10.0.0.2");
+
+        headers.insert("fly-client-ip", "198.51.100.9".parse().unwrap());
+        assert_eq!(client_ip(&headers, &peer, true), "198.51.100.9");
+        assert_eq!(client_ip(&headers, &peer, false), "10.0.0.1");
+    }
+
+    #[test]
+    fn rotation_needs_both_keys() {
+        use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let key = |seed: u8| {
+            let sk = SigningKey::from_bytes(&[seed; 32]);
+            let pk = URL_SAFE_NO_PAD.encode(sk.verifying_key().as_bytes());
+            (sk, pk)
+        };
+        let sign = |sk: &SigningKey, msg: &str| URL_SAFE_NO_PAD.encode(sk.sign(msg.as_bytes()).to_bytes());
+        let ((old_sk, old_pk), (new_sk, new_pk)) = (key(1), key(2));
+        let did = "did:sigil:parent_01";
+        let message = auth::rotate_message(did, &new_pk, 1_700_000_000, "n0nce-0123456789ab");
+
+        let mut req = RotateKeyRequest {
+            new_public_key: new_pk.clone(),
+            timestamp: 1_700_000_000,
+            nonce: "n0nce-0123456789ab".into(),
+            signature: sign(&old_sk, &message),
+            new_key_signature: sign(&new_sk, &message),
+        };
+        assert!(verify_rotation(did, &old_pk, &req).is_ok());
+        // Bound to the DID and the nonce
+        assert!(verify_rotation("did:sigil:parent_02", &old_pk, &req).is_err());
+        req.nonce = "n0nce-ba9876543210".into();
+        assert!(verify_rotation(did, &old_pk, &req).is_err());
+        req.nonce = "n0nce-0123456789ab".into();
+
+        req.new_key_signature = sign(&old_sk, &message);
+        assert!(matches!(verify_rotation(did, &old_pk, &req), Err(RegistryError::InvalidSignature(e)) if e.starts_with("new key")));
+
+        req.signature = sign(&new_sk, &message);
+        assert!(matches!(verify_rotation(did, &old_pk, &req), Err(RegistryError::InvalidSignature(e)) if e.starts_with("current key")));
+
+        req.new_public_key = old_pk.clone();
+        assert!(matches!(verify_rotation(did, &old_pk, &req), Err(RegistryError::Validation(_))));
+    }
+
+    #[test]
+    fn retired_key_is_valid_since_the_previous_rotation() {
+        let registered = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let rotated = chrono::DateTime::from_timestamp(1_710_000_000, 0).unwrap();
+        assert_eq!(retired_key_valid_from(None, registered), registered);
+        assert_eq!(retired_key_valid_from(Some(rotated), registered), rotated);
+    }
 }
@@ -0,0 +1,192 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Handlers for administering scoped API keys and registry maintenance. Every
+//! endpoint requires a key with the `admin` scope (initially the root
+//! `REGISTRY_KEY`).
+//!
+//! ## Endpoints
+//!
+//! - `GET  /admin/keys`             — List issued keys (never their secrets)
+//! - `POST /admin/keys`             — Issue a key; the plaintext is returned once
+//! - `POST /admin/keys/:id/revoke`  — Revoke a key
+//! - `POST /admin/votes/reconcile`  — Run the vote counter reconciliation now
+
+use crate::{
+    api_keys::{self, ApiKey, Principal, Scope},
+    audit,
+    db::AppState,
+    did,
+    error::RegistryError,
+    models::IssueKeyRequest,
+    votes,
+};
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Characters of the key kept in `key_prefix` for identification.
+const KEY_PREFIX_LEN: usize = 12;
+
+async fn require_admin(state: &AppState, headers: &HeaderMap) -> Result<Principal, RegistryError> {
+    let principal = api_keys::authenticate(state, headers)
+        .await?
+        .ok_or(RegistryError::Unauthorized)?;
+    principal.require(Scope::Admin)?;
+    Ok(principal)
+}
+
+// ── List ──────────────────────────────────────────────────────────────────────
+
+/// `GET /admin/keys` — All issued keys, newest first, including revoked ones.
+pub async fn list_keys(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, RegistryError> {
+    require_admin(&state, &headers).await?;
+
+    let keys = sqlx::query_as::<_, ApiKey>(
+        "SELECT id, name, key_prefix, scopes, namespaces, expires_at, last_used_at, created_at, revoked_at
+         FROM api_keys ORDER BY created_at DESC",
+    )
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(json!({ "count": keys.len(), "keys": keys })))
+}
+
+// ── Issue ─────────────────────────────────────────────────────────────────────
+
+/// `POST /admin/keys` — Issue a new scoped key.
+///
+/// The new key's scopes and namespaces must be a subset of the issuing key's;
+/// only the root key issues keys without a namespace restriction. The
+/// plaintext key is only ever returned in this response; the registry keeps
+/// its SHA-256 hash.
+pub async fn issue_key(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<IssueKeyRequest>,
+) -> Result<(StatusCode, Json<Value>), RegistryError> {
+    let admin = require_admin(&state, &headers).await?;
+
+    if req.name.trim().is_empty() {
+        return Err(RegistryError::Validation("name must not be empty".into()));
+    }
+    if req.scopes.is_empty() {
+        return Err(RegistryError::Validation("at least one scope is required".into()));
+    }
+    if let Some(bad) = req.scopes.iter().find(|s| Scope::parse(s).is_none()) {
+        return Err(RegistryError::Validation(format!(
+            "unknown scope '{bad}' (allowed: {})",
+            Scope::ALL.map(Scope::as_str).join(", ")
+        )));
+    }
+    for ns in req.namespaces.iter().flatten() {
+        did::validate_namespace(ns).map_err(RegistryError::InvalidDid)?;
+    }
+    admin.require_delegable(&req.scopes, req.namespaces.as_deref())?;
+
+    let key = api_keys::generate_key();
+    let key_prefix = &key[..KEY_PREFIX_LEN];
+
+    let mut tx = state.pool.begin().await?;
+
+    let issued = sqlx::query_as::<_, ApiKey>(
+        "INSERT INTO api_keys (name, key_prefix, key_hash, scopes, namespaces, expires_at)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         RETURNING id, name, key_prefix, scopes, namespaces, expires_at, last_used_at, created_at, revoked_at",
+    )
+    .bind(&req.name)
+    .bind(key_prefix)
+    .bind(api_keys::hash_key(&key))
+    .bind(&req.scopes)
+    .bind(&req.namespaces)
+    .bind(req.expires_at)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    audit::append(
+        &mut tx,
+        state.audit_key.as_deref(),
+        "api_key.issued",
+        &issued.id.to_string(),
+        Some(&admin.actor()),
+        json!({
+            "name": issued.name,
+            "scopes": issued.scopes,
+            "namespaces": issued.namespaces,
+            "expires_at": issued.expires_at,
+        }),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    tracing::info!("Issued API key {} ({}) with scopes {:?}", issued.id, issued.name, issued.scopes);
+
+    Ok((StatusCode::CREATED, Json(json!({ "key": key, "api_key": issued }))))
+}
+
+// ── Revoke ────────────────────────────────────────────────────────────────────
+
+/// `POST /admin/keys/:id/revoke` — Revoke a key. Takes effect on its next use.
+pub async fn revoke_key(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, RegistryError> {
+    let admin = require_admin(&state, &headers).await?;
+
+    let mut tx = state.pool.begin().await?;
+
+    let revoked = sqlx::query_as::<_, ApiKey>(
+        "UPDATE api_keys SET revoked_at = NOW()
+         WHERE id = $1 AND revoked_at IS NULL
+         RETURNING id, name, key_prefix, scopes, namespaces, expires_at, last_used_at, created_at, revoked_at",
+    )
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| RegistryError::ResourceNotFound(format!("no active API key {id}")))?;
+
+    audit::append(
+        &mut tx,
+        state.audit_key.as_deref(),
+        "api_key.revoked",
+        &id.to_string(),
+        Some(&admin.actor()),
+        json!({ "name": revoked.name }),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    tracing::info!("Revoked API key {id} ({})", revoked.name);
+
+    Ok(Json(json!({ "api_key": revoked })))
+}
+
+// ── Vote reconciliation ───────────────────────────────────────────────────────
+
+/// `POST /admin/votes/reconcile` — Recompute all vote counters from
+/// `registry_votes` without waiting for the background job.
+///
+/// Returns every entry whose counters were corrected, with the stored and the
+/// counted values.
+pub async fn reconcile_votes(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, RegistryError> {
+    require_admin(&state, &headers).await?;
+
+    let drift = votes::reconcile(&state.pool, state.audit_key.as_deref()).await?;
+
+    Ok(Json(json!({ "count": drift.len(), "drift": drift })))
+}
@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Handlers for the tamper-evident registry audit log.
+//!
+//! ## Endpoints
+//!
+//! - `GET  /audit/head`          — Latest chain entry (seq, hash, HMAC)
+//! - `GET  /audit/entries`       — Entries after `?since={seq}`, oldest first
+
+use crate::{
+    audit::{AuditEntry, GENESIS_HASH},
+    db::AppState,
+    error::RegistryError,
+    models::AuditQuery,
+};
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+// ── Head ──────────────────────────────────────────────────────────────────────
+
+/// `GET /audit/head` — The current head of the audit chain.
+///
+/// Auditors pin this value and later check that `GET /audit/entries` still
+/// replays to it. An empty log reports `seq: 0` and the genesis hash.
+pub async fn audit_head(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Value>, RegistryError> {
+    let head = sqlx::query_as::<_, AuditEntry>(
+        "SELECT * FROM audit_log ORDER BY seq DESC LIMIT 1",
+    )
+    .fetch_optional(&state.pool)
+    .await?;
+
+    Ok(Json(match head {
+        Some(e) => json!({
+            "seq": e.seq,
+            "entry_hash": e.entry_hash,
+            "head_mac": e.head_mac,
+            "occurred_at": e.occurred_at,
+        }),
+        None => json!({ "seq": 0, "entry_hash": GENESIS_HASH, "head_mac": null }),
+    }))
+}
+
+// ── Entries ───────────────────────────────────────────────────────────────────
+
+/// `GET /audit/entries?since={seq}&limit={n}` — Chain entries with `seq > since`.
+///
+/// `prev_hash` is the stored hash of the entry preceding the page (the genesis
+/// hash for `since=0`), read independently of the page itself, so the page can
+/// be verified with `audit::verify_chain` against it.
+pub async fn audit_entries(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<AuditQuery>,
+) -> Result<Json<Value>, RegistryError> {
+    let since = q.since.unwrap_or(0).max(0);
+    let limit = q.limit.unwrap_or(100).clamp(1, 1000);
+
+    let entries = sqlx::query_as::<_, AuditEntry>(
+        "SELECT * FROM audit_log WHERE seq > $1 ORDER BY seq LIMIT $2",
+    )
+    .bind(since)
+    .bind(limit)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let prev_hash: String = sqlx::query_scalar(
+        "SELECT entry_hash FROM audit_log WHERE seq <= $1 ORDER BY seq DESC LIMIT 1",
+    )
+    .bind(since)
+    .fetch_optional(&state.pool)
+    .await?
+    .unwrap_or_else(|| GENESIS_HASH.to_string());
+
+    Ok(Json(json!({
+        "since": since,
+        "count": entries.len(),
+        "prev_hash": prev_hash,
+        "entries": entries,
+    })))
+}
@@ -0,0 +1,327 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Handlers for maintainer moderation of patterns and policies.
+//!
+//! Every endpoint requires an `X-Registry-Key` with the `moderate` scope.
+//! Decisions are additionally signed by the maintainer's `did:sigil:` key, so
+//! each one is attributed to a person rather than a shared credential. Only
+//! DIDs listed in `MAINTAINER_DIDS` may sign decisions, and each signature
+//! carries a single-use nonce so a decision cannot be replayed to undo a later one.
+//!
+//! ## Endpoints
+//!
+//! - `GET  /moderation/queue`            — Active entries awaiting review
+//! - `POST /patterns/:id/approve`        — Verify a pattern (it enters the bundle)
+//! - `POST /patterns/:id/reject`         — Reject a pattern, with a reason
+//! - `POST /patterns/:id/deactivate`     — Deactivate a pattern, with a reason
+//! - `POST /policies/:id/approve`        — Verify a policy
+//! - `POST /policies/:id/reject`         — Reject a policy, with a reason
+//! - `POST /policies/:id/deactivate`     — Deactivate a policy, with a reason
+
+use crate::{
+    api_keys::{self, Scope},
+    audit, auth,
+    db::AppState,
+    error::RegistryError,
+    handlers_patterns, handlers_policies,
+    models::{ModerationQuery, ModerationRequest, ScannerPattern, SecurityPolicy},
+    replay,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// What a moderation request is about.
+#[derive(Debug, Clone, Copy)]
+enum Target {
+    Pattern,
+    Policy,
+}
+
+impl Target {
+    fn as_str(self) -> &'static str {
+        match self {
+            Target::Pattern => "pattern",
+            Target::Policy => "policy",
+        }
+    }
+
+    fn table(self) -> &'static str {
+        match self {
+            Target::Pattern => "scanner_patterns",
+            Target::Policy => "security_policies",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Target::Pattern => "Pattern",
+            Target::Policy => "Policy",
+        }
+    }
+}
+
+/// A moderation decision.
+#[derive(Debug, Clone, Copy)]
+enum Action {
+    Approve,
+    Reject,
+    Deactivate,
+}
+
+impl Action {
+    fn as_str(self) -> &'static str {
+        match self {
+            Action::Approve => "approve",
+            Action::Reject => "reject",
+            Action::Deactivate => "deactivate",
+        }
+    }
+
+    /// Audit action suffix, e.g. `pattern.approved`.
+    fn past_tense(self) -> &'static str {
+        match self {
+            Action::Approve => "approved",
+            Action::Reject => "rejected",
+            Action::Deactivate => "deactivated",
+        }
+    }
+
+    /// Column assignments applied alongside the moderator fields.
+    fn assignments(self) -> &'static str {
+        match self {
+            Action::Approve => "verified = TRUE, review_status = 'approved'",
+            Action::Reject => "verified = FALSE, review_status = 'rejected'",
+            Action::Deactivate => "active = FALSE",
+        }
+    }
+}
+
+// ── Queue ─────────────────────────────────────────────────────────────────────
+
+/// `GET /moderation/queue` — Active patterns and policies awaiting review, oldest first.
+pub async fn moderation_queue(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(q): Query<ModerationQuery>,
+) -> Result<Json<Value>, RegistryError> {
+    api_keys::authenticate(&state, &headers)
+        .await?
+        .ok_or(RegistryError::Unauthorized)?
+        .require(Scope::Moderate)?;
+
+    let limit = q.limit.unwrap_or(50).clamp(1, 200);
+    let offset = q.offset.unwrap_or(0).max(0);
+
+    let patterns = sqlx::query_as::<_, ScannerPattern>(
+        "SELECT * FROM scanner_patterns
+         WHERE active = TRUE AND review_status = 'pending'
+         ORDER BY created_at
+         LIMIT $1 OFFSET $2",
+    )
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let policies = sqlx::query_as::<_, SecurityPolicy>(
+        "SELECT * FROM security_policies
+         WHERE active = TRUE AND review_status = 'pending'
+         ORDER BY created_at
+         LIMIT $1 OFFSET $2",
+    )
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(json!({
+        "count": patterns.len() + policies.len(),
+        "offset": offset,
+        "patterns": patterns,
+        "policies": policies,
+    })))
+}
+
+// ── Decisions ─────────────────────────────────────────────────────────────────
+
+/// `POST /patterns/:id/approve`
+pub async fn approve_pattern(
+    state: State<Arc<AppState>>,
+    id: Path<Uuid>,
+    headers: HeaderMap,
+    req: Json<ModerationRequest>,
+) -> Result<Json<Value>, RegistryError> {
+    moderate(state, id, headers, req, Target::Pattern, Action::Approve).await
+}
+
+/// `POST /patterns/:id/reject`
+pub async fn reject_pattern(
+    state: State<Arc<AppState>>,
+    id: Path<Uuid>,
+    headers: HeaderMap,
+    req: Json<ModerationRequest>,
+) -> Result<Json<Value>, RegistryError> {
+    moderate(state, id, headers, req, Target::Pattern, Action::Reject).await
+}
+
+/// `POST /patterns/:id/deactivate`
+pub async fn deactivate_pattern(
+    state: State<Arc<AppState>>,
+    id: Path<Uuid>,
+    headers: HeaderMap,
+    req: Json<ModerationRequest>,
+) -> Result<Json<Value>, RegistryError> {
+    moderate(state, id, headers, req, Target::Pattern, Action::Deactivate).await
+}
+
+/// `POST /policies/:id/approve`
+pub async fn approve_policy(
+    state: State<Arc<AppState>>,
+    id: Path<Uuid>,
+    headers: HeaderMap,
+    req: Json<ModerationRequest>,
+) -> Result<Json<Value>, RegistryError> {
+    moderate(state, id, headers, req, Target::Policy, Action::Approve).await
+}
+
+/// `POST /policies/:id/reject`
+pub async fn reject_policy(
+    state: State<Arc<AppState>>,
+    id: Path<Uuid>,
+    headers: HeaderMap,
+    req: Json<ModerationRequest>,
+) -> Result<Json<Value>, RegistryError> {
+    moderate(state, id, headers, req, Target::Policy, Action::Reject).await
+}
+
+/// `POST /policies/:id/deactivate`
+pub async fn deactivate_policy(
+    state: State<Arc<AppState>>,
+    id: Path<Uuid>,
+    headers: HeaderMap,
+    req: Json<ModerationRequest>,
+) -> Result<Json<Value>, RegistryError> {
+    moderate(state, id, headers, req, Target::Policy, Action::Deactivate).await
+}
+
+/// Authorise, apply and audit a moderation decision on an active entry.
+async fn moderate(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(req): Json<ModerationRequest>,
+    target: Target,
+    action: Action,
+) -> Result<Json<Value>, RegistryError> {
+    // 1. The shared credential: a key with the moderate scope
+    let principal = api_keys::authenticate(&state, &headers)
+        .await?
+        .ok_or(RegistryError::Unauthorized)?;
+    principal.require(Scope::Moderate)?;
+
+    // 2. Rejections and deactivations must say why
+    let reason = req.reason.as_deref().map(str::trim).filter(|r| !r.is_empty());
+    if reason.is_none() && !matches!(action, Action::Approve) {
+        return Err(RegistryError::Validation(format!(
+            "a reason is required to {} a {}",
+            action.as_str(),
+            target.as_str()
+        )));
+    }
+
+    // 3. The individual maintainer signs the decision
+    if !state.maintainer_dids.contains(&req.moderator_did) {
+        return Err(RegistryError::Forbidden(format!(
+            "{} is not a registry maintainer",
+            req.moderator_did
+        )));
+    }
+
+    auth::check_timestamp(req.timestamp, chrono::Utc::now().timestamp(), state.replay.max_skew_secs)
+        .map_err(RegistryError::InvalidSignature)?;
+
+    let moderator_key: Option<String> = sqlx::query_scalar(
+        "SELECT public_key FROM dids WHERE did = $1 AND status = 'active'",
+    )
+    .bind(&req.moderator_did)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    let public_key = moderator_key.ok_or_else(|| RegistryError::UnknownAuthor(req.moderator_did.clone()))?;
+
+    let message = auth::moderation_message(
+        target.as_str(),
+        &id.to_string(),
+        action.as_str(),
+        req.reason.as_deref().unwrap_or(""),
+        req.timestamp,
+        &req.nonce,
+    );
+    auth::verify_signature(&public_key, &message, &req.signature)
+        .map_err(RegistryError::InvalidSignature)?;
+    replay::use_nonce(&state, &req.moderator_did, &req.nonce).await?;
+
+    // 4. Apply and record in the audit chain
+    let mut tx = state.pool.begin().await?;
+
+    let updated = sqlx::query(&format!(
+        "UPDATE {table}
+         SET {assignments},
+             moderated_by = $2, moderated_at = NOW(), moderation_reason = $3, updated_at = NOW()
+         WHERE id = $1 AND active = TRUE",
+        table = target.table(),
+        assignments = action.assignments(),
+    ))
+    .bind(id)
+    .bind(&req.moderator_did)
+    .bind(reason)
+    .execute(&mut *tx)
+    .await?;
+
+    if updated.rows_affected() == 0 {
+        return Err(RegistryError::ResourceNotFound(format!("{} {id} not found", target.label())));
+    }
+
+    audit::append(
+        &mut tx,
+        state.audit_key.as_deref(),
+        &format!("{}.{}", target.as_str(), action.past_tense()),
+        &id.to_string(),
+        Some(&req.moderator_did),
+        json!({ "reason": reason, "api_key": principal.key_id }),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    // Record the new verified set in the bundle revision history right away
+    let refreshed = match target {
+        Target::Pattern => handlers_patterns::pattern_bundle(&state).await.map(|_| ()),
+        Target::Policy => handlers_policies::policy_bundle(&state).await.map(|_| ()),
+    };
+    if let Err(e) = refreshed {
+        tracing::warn!("{} bundle revision refresh failed: {e}", target.as_str());
+    }
+
+    tracing::info!(
+        "{} {id} {} by {}",
+        target.label(),
+        action.past_tense(),
+        req.moderator_did
+    );
+
+    Ok(Json(json!({
+        "id": id,
+        "action": action.as_str(),
+        "moderated_by": req.moderator_did,
+        "reason": reason,
+    })))
+}
@@ -0,0 +1,518 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Handlers for namespace ownership and delegated registration.
+//!
+//! ## Endpoints
+//!
+//! - `GET  /namespaces`                  — List claimed namespaces
+//! - `POST /namespaces`                  — Claim a namespace (signed by the controller DID)
+//! - `GET  /namespaces/:name`            — Namespace, controller and delegates
+//! - `GET  /namespaces/:name/dids`       — DIDs registered in the namespace
+//! - `POST /namespaces/:name/transfer`   — Hand control to another DID
+//! - `POST /namespaces/:name/delegates`  — Grant or revoke a delegated sub-controller
+//!
+//! Once claimed, `POST /register` into a namespace (or any unclaimed namespace
+//! nested under it, e.g. `acme_eu` under `acme`) requires a signature from the
+//! controller or a delegate. The most specific claimed namespace governs.
+
+use crate::{
+    api_keys::{self, Scope},
+    audit, auth,
+    db::AppState,
+    did,
+    error::RegistryError,
+    models::{
+        ClaimNamespaceRequest, DelegateRequest, DidDocument, Namespace, NamespaceQuery,
+        RegisterRequest, TransferNamespaceRequest,
+    },
+    replay,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// Advisory lock class serializing claims within one namespace tree.
+const CLAIM_LOCK_CLASS: i32 = 0x5349_474e; // "SIGN"
+
+// ── List ──────────────────────────────────────────────────────────────────────
+
+/// `GET /namespaces` — List claimed namespaces.
+pub async fn list_namespaces(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<NamespaceQuery>,
+) -> Result<Json<Value>, RegistryError> {
+    let limit = q.limit.unwrap_or(50).clamp(1, 200);
+    let offset = q.offset.unwrap_or(0).max(0);
+
+    let namespaces = sqlx::query_as::<_, Namespace>(
+        "SELECT * FROM namespaces ORDER BY name LIMIT $1 OFFSET $2",
+    )
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(json!({
+        "count": namespaces.len(),
+        "offset": offset,
+        "namespaces": namespaces,
+    })))
+}
+
+// ── Get one ───────────────────────────────────────────────────────────────────
+
+/// `GET /namespaces/:name` — A claimed namespace with its delegates.
+pub async fn get_namespace(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Result<Json<Value>, RegistryError> {
+    let namespace = fetch_namespace(&state.pool, &name).await?;
+
+    let delegates: Vec<String> = sqlx::query_scalar(
+        "SELECT delegate_did FROM namespace_delegates WHERE namespace = $1 ORDER BY granted_at",
+    )
+    .bind(&name)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let members: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM dids WHERE namespace = $1")
+        .bind(&name)
+        .fetch_one(&state.pool)
+        .await?;
+
+    Ok(Json(json!({
+        "namespace": namespace,
+        "delegates": delegates,
+        "member_count": members,
+    })))
+}
+
+/// `GET /namespaces/:name/dids` — DIDs registered in a namespace.
+///
+/// Works for unclaimed namespaces too, so squatting can be spotted before a claim.
+pub async fn list_namespace_dids(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Query(q): Query<NamespaceQuery>,
+) -> Result<Json<Value>, RegistryError> {
+    did::validate_namespace(&name).map_err(RegistryError::InvalidDid)?;
+
+    let limit = q.limit.unwrap_or(50).clamp(1, 200);
+    let offset = q.offset.unwrap_or(0).max(0);
+
+    let dids = sqlx::query_as::<_, DidDocument>(
+        "SELECT did, public_key, namespace, label, status, created_at, updated_at, revoked_at
+         FROM dids WHERE namespace = $1
+         ORDER BY created_at, did
+         LIMIT $2 OFFSET $3",
+    )
+    .bind(&name)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(json!({
+        "namespace": name,
+        "count": dids.len(),
+        "offset": offset,
+        "dids": dids,
+    })))
+}
+
+// ── Claim ─────────────────────────────────────────────────────────────────────
+
+/// `POST /namespaces` — Claim an unclaimed namespace.
+///
+/// The controller DID signs the claim. A namespace nested under a claimed one
+/// can only be claimed by that parent's controller, and a namespace that would
+/// govern other parties' DIDs needs an API key with the `moderate` scope.
+pub async fn claim_namespace(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<ClaimNamespaceRequest>,
+) -> Result<(StatusCode, Json<Value>), RegistryError> {
+    did::validate_namespace(&req.name).map_err(RegistryError::InvalidDid)?;
+    did::validate(&req.controller_did).map_err(RegistryError::InvalidDid)?;
+
+    auth::check_timestamp(req.timestamp, chrono::Utc::now().timestamp(), state.replay.max_skew_secs)
+        .map_err(RegistryError::InvalidSignature)?;
+    let message = auth::namespace_claim_message(&req.name, &req.controller_did, req.timestamp);
+    verify_did_signature(&state.pool, &req.controller_did, &message, &req.signature).await?;
+
+    // Claims in one tree (`acme`, `acme_eu`, ...) are serialized so a parent
+    // claimed concurrently cannot be missed by the check below
+    let mut tx = state.pool.begin().await?;
+    let root = did::enclosing_namespaces(&req.name).pop().unwrap_or(&req.name);
+    sqlx::query("SELECT pg_advisory_xact_lock($1, hashtext($2))")
+        .bind(CLAIM_LOCK_CLASS)
+        .bind(root)
+        .execute(&mut *tx)
+        .await?;
+
+    // Nested namespaces belong to whoever controls the enclosing one
+    let ancestors: Vec<String> = did::enclosing_namespaces(&req.name)
+        .into_iter()
+        .skip(1)
+        .map(str::to_string)
+        .collect();
+    let parent: Option<Namespace> = sqlx::query_as(
+        "SELECT * FROM namespaces WHERE name = ANY($1) ORDER BY length(name) DESC LIMIT 1",
+    )
+    .bind(&ancestors)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    if let Some(parent) = parent.filter(|p| p.controller_did != req.controller_did) {
+        return Err(RegistryError::Forbidden(format!(
+            "'{}' is nested under namespace '{}', which is controlled by another DID",
+            req.name, parent.name
+        )));
+    }
+
+    // Any DID the claim would govern counts, whether it was filed under this
+    // namespace, a nested one, or an enclosing one (`acme_eu_01` under `acme`)
+    let squatted: bool = sqlx::query_scalar(
+        "SELECT EXISTS(
+             SELECT 1 FROM dids
+             WHERE did <> $2
+               AND (namespace = $1
+                 OR starts_with(namespace, $1 || '_')
+                 OR did = 'did:sigil:' || $1
+                 OR starts_with(did, 'did:sigil:' || $1 || '_')))",
+    )
+    .bind(&req.name)
+    .bind(&req.controller_did)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if squatted {
+        let approved = api_keys::authenticate(&state, &headers)
+            .await?
+            .is_some_and(|p| p.has(Scope::Moderate));
+        if !approved {
+            return Err(RegistryError::Forbidden(format!(
+                "namespace '{}' already holds DIDs of other controllers; a moderator must approve the claim",
+                req.name
+            )));
+        }
+    }
+
+    let inserted = sqlx::query(
+        "INSERT INTO namespaces (name, controller_did) VALUES ($1, $2)
+         ON CONFLICT (name) DO NOTHING",
+    )
+    .bind(&req.name)
+    .bind(&req.controller_did)
+    .execute(&mut *tx)
+    .await?;
+
+    if inserted.rows_affected() == 0 {
+        return Err(RegistryError::Duplicate(format!(
+            "Namespace '{}' is already claimed",
+            req.name
+        )));
+    }
+
+    audit::append(
+        &mut tx,
+        state.audit_key.as_deref(),
+        "namespace.claimed",
+        &req.name,
+        Some(&req.controller_did),
+        json!({ "controller_did": req.controller_did }),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    tracing::info!("Namespace '{}' claimed by {}", req.name, req.controller_did);
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "name": req.name,
+            "controller_did": req.controller_did,
+            "message": "Namespace claimed. Registrations in it now require a controller signature.",
+        })),
+    ))
+}
+
+// ── Transfer ──────────────────────────────────────────────────────────────────
+
+/// `POST /namespaces/:name/transfer` — Hand a namespace to a new controller.
+///
+/// Signed by the current controller (with a single-use `nonce`), or performed
+/// with an API key holding the `moderate` scope. Existing delegates are kept.
+pub async fn transfer_namespace(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<TransferNamespaceRequest>,
+) -> Result<Json<Value>, RegistryError> {
+    did::validate(&req.new_controller_did).map_err(RegistryError::InvalidDid)?;
+    let namespace = fetch_namespace(&state.pool, &name).await?;
+
+    let principal = api_keys::authenticate(&state, &headers).await?;
+    if let Some(p) = &principal {
+        p.require(Scope::Moderate)?;
+    } else {
+        let (Some(timestamp), Some(signature)) = (req.timestamp, req.signature.as_deref()) else {
+            return Err(RegistryError::Unauthorized);
+        };
+        let nonce = req
+            .nonce
+            .as_deref()
+            .ok_or_else(|| RegistryError::Validation("a signed transfer requires a nonce".into()))?;
+        auth::check_timestamp(timestamp, chrono::Utc::now().timestamp(), state.replay.max_skew_secs)
+            .map_err(RegistryError::InvalidSignature)?;
+        let message = auth::namespace_transfer_message(&name, &req.new_controller_did, timestamp, nonce);
+        verify_did_signature(&state.pool, &namespace.controller_did, &message, signature).await?;
+        replay::use_nonce(&state, &namespace.controller_did, nonce).await?;
+    }
+
+    // The new controller must be a live DID
+    active_key(&state.pool, &req.new_controller_did).await?;
+
+    let mut tx = state.pool.begin().await?;
+
+    sqlx::query("UPDATE namespaces SET controller_did = $2, updated_at = NOW() WHERE name = $1")
+        .bind(&name)
+        .bind(&req.new_controller_did)
+        .execute(&mut *tx)
+        .await?;
+
+    audit::append(
+        &mut tx,
+        state.audit_key.as_deref(),
+        "namespace.transferred",
+        &name,
+        Some(&principal.map_or_else(|| namespace.controller_did.clone(), |p| p.actor())),
+        json!({ "from": namespace.controller_did, "to": req.new_controller_did }),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    tracing::warn!(
+        "Namespace '{}' transferred from {} to {}",
+        name, namespace.controller_did, req.new_controller_did
+    );
+
+    Ok(Json(json!({ "name": name, "controller_did": req.new_controller_did })))
+}
+
+// ── Delegates ─────────────────────────────────────────────────────────────────
+
+/// `POST /namespaces/:name/delegates` — Grant or revoke a delegated sub-controller.
+///
+/// Signed by the namespace controller, with a single-use `nonce` so that a
+/// revocation cannot be undone by replaying the grant. Delegates may authorise
+/// registrations but cannot transfer the namespace or manage other delegates.
+pub async fn update_delegate(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Json(req): Json<DelegateRequest>,
+) -> Result<Json<Value>, RegistryError> {
+    did::validate(&req.delegate_did).map_err(RegistryError::InvalidDid)?;
+    let namespace = fetch_namespace(&state.pool, &name).await?;
+
+    auth::check_timestamp(req.timestamp, chrono::Utc::now().timestamp(), state.replay.max_skew_secs)
+        .map_err(RegistryError::InvalidSignature)?;
+    let message =
+        auth::namespace_delegate_message(&name, &req.delegate_did, req.grant, req.timestamp, &req.nonce);
+    verify_did_signature(&state.pool, &namespace.controller_did, &message, &req.signature).await?;
+    replay::use_nonce(&state, &namespace.controller_did, &req.nonce).await?;
+
+    if req.grant {
+        // Delegates must be live DIDs
+        active_key(&state.pool, &req.delegate_did).await?;
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    if req.grant {
+        sqlx::query(
+            "INSERT INTO namespace_delegates (namespace, delegate_did) VALUES ($1, $2)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(&name)
+        .bind(&req.delegate_did)
+        .execute(&mut *tx)
+        .await?;
+    } else {
+        sqlx::query("DELETE FROM namespace_delegates WHERE namespace = $1 AND delegate_did = $2")
+            .bind(&name)
+            .bind(&req.delegate_did)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    audit::append(
+        &mut tx,
+        state.audit_key.as_deref(),
+        if req.grant { "namespace.delegate_granted" } else { "namespace.delegate_revoked" },
+        &name,
+        Some(&namespace.controller_did),
+        json!({ "delegate_did": req.delegate_did }),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(json!({
+        "name": name,
+        "delegate_did": req.delegate_did,
+        "granted": req.grant,
+    })))
+}
+
+// ── Registration gate ─────────────────────────────────────────────────────────
+
+/// Enforce namespace ownership for `POST /register`.
+///
+/// Finds the most specific claimed namespace enclosing the DID. If there is
+/// one, the request must be filed under that namespace (or an unclaimed one
+/// nested inside it) and carry a signature from its controller or a delegate.
+/// DIDs outside any claimed namespace pass.
+pub(crate) async fn authorize_registration(
+    pool: &PgPool,
+    req: &RegisterRequest,
+) -> Result<(), RegistryError> {
+    let id = did::validate(&req.did).map_err(RegistryError::InvalidDid)?;
+    let candidates: Vec<String> = did::enclosing_namespaces(id)
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+
+    let governing: Option<Namespace> = sqlx::query_as(
+        "SELECT * FROM namespaces WHERE name = ANY($1) ORDER BY length(name) DESC LIMIT 1",
+    )
+    .bind(&candidates)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(namespace) = governing else {
+        return Ok(());
+    };
+
+    // `req.namespace` already encloses the DID, so a shorter one sits above the claim
+    if req.namespace.len() < namespace.name.len() {
+        return Err(RegistryError::Forbidden(format!(
+            "{} falls under claimed namespace '{}'",
+            req.did, namespace.name
+        )));
+    }
+
+    let (Some(signer), Some(signature)) =
+        (req.controller_did.as_deref(), req.controller_signature.as_deref())
+    else {
+        return Err(RegistryError::Forbidden(format!(
+            "namespace '{}' is claimed; controller_did and controller_signature are required",
+            namespace.name
+        )));
+    };
+
+    let is_delegate: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM namespace_delegates WHERE namespace = $1 AND delegate_did = $2)",
+    )
+    .bind(&namespace.name)
+    .bind(signer)
+    .fetch_one(pool)
+    .await?;
+
+    if signer != namespace.controller_did && !is_delegate {
+        return Err(RegistryError::Forbidden(format!(
+            "{signer} is neither controller nor delegate of namespace '{}'",
+            namespace.name
+        )));
+    }
+
+    let message =
+        auth::namespace_register_message(&namespace.name, &req.did, &req.public_key, &req.nonce);
+    verify_did_signature(pool, signer, &message, signature).await
+}
+
+/// Enforce the longest-namespace rule for `POST /register` (see [`crate::did`]).
+///
+/// The DID must be filed under the longest namespace in use — claimed or
+/// holding DIDs — that encloses it, and a namespace must not be opened over
+/// DIDs already filed under a shorter one.
+pub(crate) async fn check_most_specific(
+    pool: &PgPool,
+    req: &RegisterRequest,
+) -> Result<(), RegistryError> {
+    let id = did::validate(&req.did).map_err(RegistryError::InvalidDid)?;
+    let candidates: Vec<String> = did::enclosing_namespaces(id)
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+
+    let known: Vec<String> = sqlx::query_scalar(
+        "SELECT name FROM namespaces WHERE name = ANY($1)
+         UNION
+         SELECT DISTINCT namespace FROM dids WHERE namespace = ANY($1)",
+    )
+    .bind(&candidates)
+    .fetch_all(pool)
+    .await?;
+
+    if let Some(owner) = did::owning_namespace(id, &known).filter(|o| o.len() > req.namespace.len()) {
+        return Err(RegistryError::InvalidDid(did::DidError::ShadowedNamespace(owner.to_string())));
+    }
+
+    let taken_over: Option<String> = sqlx::query_scalar(
+        "SELECT did FROM dids
+         WHERE length(namespace) < length($1)
+           AND (did = 'did:sigil:' || $1 OR starts_with(did, 'did:sigil:' || $1 || '_'))
+         LIMIT 1",
+    )
+    .bind(&req.namespace)
+    .fetch_optional(pool)
+    .await?;
+
+    if let Some(existing) = taken_over {
+        return Err(RegistryError::Forbidden(format!(
+            "namespace '{}' would take over {existing}, which is filed under a shorter namespace",
+            req.namespace
+        )));
+    }
+    Ok(())
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+async fn fetch_namespace(pool: &PgPool, name: &str) -> Result<Namespace, RegistryError> {
+    sqlx::query_as::<_, Namespace>("SELECT * FROM namespaces WHERE name = $1")
+        .bind(name)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| RegistryError::ResourceNotFound(format!("Namespace '{name}' not found")))
+}
+
+/// Public key of an active DID, or `UnknownAuthor`.
+async fn active_key(pool: &PgPool, did: &str) -> Result<String, RegistryError> {
+    sqlx::query_scalar("SELECT public_key FROM dids WHERE did = $1 AND status = 'active'")
+        .bind(did)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| RegistryError::UnknownAuthor(did.to_string()))
+}
+
+/// Verify `signature` over `message` against `did`'s current key.
+async fn verify_did_signature(
+    pool: &PgPool,
+    did: &str,
+    message: &str,
+    signature: &str,
+) -> Result<(), RegistryError> {
+    let public_key = active_key(pool, did).await?;
+    auth::verify_signature(&public_key, message, signature).map_err(RegistryError::InvalidSignature)
+}
@@ -8,29 +8,49 @@
 //!
 //! - `GET  /patterns`            — List patterns (filterable by category/verified)
 //! - `GET  /patterns/bundle`     — Compiled bundle of all verified patterns (for SDK use)
+//! - `GET  /patterns/bundle/delta` — Changes since `?since={revision}`
 //! - `GET  /patterns/:id`        — Get a single pattern
+//! - `GET  /patterns/:id/stats`  — Daily bundle downloads of a pattern
+//! - `GET  /patterns/:id/overlaps` — Verified patterns matching the same samples
+//! - `GET  /patterns/:id/versions` — All versions of a pattern, with diffs
 //! - `POST /patterns`            — Submit a new pattern (requires Ed25519 signature)
+//! - `PUT  /patterns/:id`        — Submit a new version (signed by the original author)
 //! - `POST /patterns/:id/vote`   — Vote on a pattern (requires Ed25519 signature)
 
 use crate::{
-    auth,
+    audit, auth,
+    bundle::{self, BundleRevision},
     db::AppState,
+    downloads,
     error::RegistryError,
-    models::{BundleEntry, CreatePatternRequest, PatternQuery, ScannerPattern, VoteRequest},
+    models::{
+        BundleDeltaQuery, BundleEntry, CreatePatternRequest, PatternQuery, PatternVersion, ScannerPattern,
+        StatsQuery, UpdatePatternRequest, VoteRequest,
+    },
+    pattern_overlap::{self, Overlap, SampledSet},
+    pattern_screening::{self, PatternExamples},
+    rate_limit::{self, Route},
+    replay,
+    votes::{self, Ballot, Target},
 };
 use axum::{
     extract::{Path, Query, State},
-    http::{header, StatusCode},
+    http::{HeaderMap, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
+use chrono::{DateTime, Utc};
+use regex::Regex;
 use serde_json::{json, Value};
-use std::sync::Arc;
+use sqlx::PgPool;
+use std::{collections::HashMap, sync::Arc};
 use uuid::Uuid;
 
 // ── List ──────────────────────────────────────────────────────────────────────
 
 /// `GET /patterns` — List scanner patterns, optionally filtered.
+///
+/// Ordered by reputation-weighted vote `score`, then raw up-votes and downloads.
 pub async fn list_patterns(
     State(state): State<Arc<AppState>>,
     Query(q): Query<PatternQuery>,
@@ -42,7 +62,7 @@ pub async fn list_patterns(
         (Some(cat), Some(v)) => sqlx::query_as::<_, ScannerPattern>(
             "SELECT * FROM scanner_patterns
              WHERE active = TRUE AND category = $1 AND verified = $2
-             ORDER BY votes_up DESC, downloads DESC
+             ORDER BY score DESC, votes_up DESC, downloads DESC
              LIMIT $3 OFFSET $4",
         )
         .bind(cat)
@@ -55,7 +75,7 @@ pub async fn list_patterns(
         (Some(cat), None) => sqlx::query_as::<_, ScannerPattern>(
             "SELECT * FROM scanner_patterns
              WHERE active = TRUE AND category = $1
-             ORDER BY votes_up DESC, downloads DESC
+             ORDER BY score DESC, votes_up DESC, downloads DESC
              LIMIT $2 OFFSET $3",
         )
         .bind(cat)
@@ -67,7 +87,7 @@ pub async fn list_patterns(
         (None, Some(v)) => sqlx::query_as::<_, ScannerPattern>(
             "SELECT * FROM scanner_patterns
              WHERE active = TRUE AND verified = $1
-             ORDER BY votes_up DESC, downloads DESC
+             ORDER BY score DESC, votes_up DESC, downloads DESC
              LIMIT $2 OFFSET $3",
         )
         .bind(v)
@@ -79,7 +99,7 @@ pub async fn list_patterns(
         (None, None) => sqlx::query_as::<_, ScannerPattern>(
             "SELECT * FROM scanner_patterns
              WHERE active = TRUE
-             ORDER BY votes_up DESC, downloads DESC
+             ORDER BY score DESC, votes_up DESC, downloads DESC
              LIMIT $1 OFFSET $2",
         )
         .bind(limit)
@@ -104,74 +124,181 @@ pub async fn list_patterns(
 ///
 /// The response is marked `Cache-Control: public, max-age=3600` so that CDNs
 /// (e.g. Cloudflare) can serve it from cache for up to 1 hour, dramatically
-/// reducing origin load when many clients start up simultaneously.
+/// reducing origin load when many clients start up simultaneously. Because the
+/// CDN is untrusted, the body carries a detached Ed25519 signature in the
+/// `X-Sigil-*` headers (see [`crate::bundle_signing`]).
+///
+/// The body is byte-identical for a given `revision`; the `ETag` is the
+/// revision plus its content hash, and `If-None-Match` revalidation answers
+/// `304 Not Modified`.
 pub async fn get_bundle(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
 ) -> Response {
-    let patterns = match sqlx::query_as::<_, ScannerPattern>(
-        "SELECT * FROM scanner_patterns
-         WHERE active = TRUE AND verified = TRUE
-         ORDER BY category, name",
-    )
-    .fetch_all(&state.pool)
-    .await
-    {
-        Ok(p) => p,
+    let (bundle, rev) = match pattern_bundle(&state).await {
+        Ok(b) => b,
         Err(e) => {
             tracing::error!("get_bundle DB error: {e}");
             return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "database error" }))).into_response();
         }
     };
 
-    // Fire-and-forget: increment download counters without blocking the response
-    let pool = state.pool.clone();
-    tokio::spawn(async move {
-        let _ = sqlx::query(
-            "UPDATE scanner_patterns SET downloads = downloads + 1
-             WHERE active = TRUE AND verified = TRUE",
-        )
-        .execute(&pool)
-        .await;
-    });
-
-    let bundle: Vec<BundleEntry> = patterns
-        .into_iter()
-        .map(|p| BundleEntry {
-            name: p.name,
-            category: p.category,
-            pattern: p.pattern,
-            severity: p.severity,
-            replacement_hint: p.replacement_hint,
-        })
-        .collect();
+    // A 304 revalidation is not a download
+    if !bundle::not_modified(&headers, &rev.etag()) {
+        downloads::record(&state.pool, rev.revision);
+    }
 
     let body = json!({
         "version": "1",
-        "generated_at": chrono::Utc::now(),
+        "revision": rev.revision,
+        "generated_at": rev.created_at,
         "count": bundle.len(),
         "patterns": bundle,
     });
 
-    (
-        StatusCode::OK,
-        [
-            (header::CACHE_CONTROL, "public, max-age=3600"),
-            (header::VARY, "Accept-Encoding"),
-        ],
-        Json(body),
+    bundle::respond(&state, &headers, &rev, &rev.etag(), &body)
+}
+
+/// `GET /patterns/bundle/delta?since={revision}` — Changes since an earlier bundle revision.
+///
+/// Returns patterns `added` and `changed` in full and `removed` by name. When
+/// `since` is unknown, in the future, or older than the retained history, the
+/// response carries `full_resync: true` and the client should fetch
+/// `GET /patterns/bundle` instead. The `ETag` names `since` and the current
+/// revision, so `If-None-Match` revalidates a delta only against itself.
+pub async fn get_bundle_delta(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<BundleDeltaQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let (entries, rev) = match pattern_bundle(&state).await {
+        Ok(b) => b,
+        Err(e) => {
+            tracing::error!("get_bundle_delta DB error: {e}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "database error" }))).into_response();
+        }
+    };
+
+    let old = match bundle::snapshot(&state.pool, "patterns", q.since).await {
+        Ok(old) => old,
+        Err(e) => {
+            tracing::error!("get_bundle_delta DB error: {e}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "database error" }))).into_response();
+        }
+    };
+
+    let body = match old {
+        Some(old) => {
+            let delta = bundle::delta(&old, &json!(entries), "name");
+            json!({
+                "version": "1",
+                "since": q.since,
+                "revision": rev.revision,
+                "full_resync": false,
+                "added": delta.added,
+                "changed": delta.changed,
+                "removed": delta.removed,
+            })
+        }
+        None => json!({
+            "version": "1",
+            "since": q.since,
+            "revision": rev.revision,
+            "full_resync": true,
+        }),
+    };
+
+    bundle::respond(&state, &headers, &rev, &rev.delta_etag(q.since), &body)
+}
+
+/// The current verified pattern set and its bundle revision.
+///
+/// A pattern whose latest version awaits re-review is bundled at its newest
+/// earlier version that was verified, if any.
+///
+/// Called after every change to the verified set (moderation, edits) so the
+/// revision history records it, and on every bundle read to catch changes
+/// made directly in the database.
+pub(crate) async fn pattern_bundle(state: &AppState) -> anyhow::Result<(Vec<BundleEntry>, BundleRevision)> {
+    let entries: Vec<BundleEntry> = bundled_versions(&state.pool)
+        .await?
+        .into_iter()
+        .map(|row| row.entry)
+        .collect();
+
+    let hash = bundle::content_hash(&entries)?;
+
+    // Moderation bumps updated_at on approved, rejected and deactivated rows alike
+    let last_modified: Option<DateTime<Utc>> = sqlx::query_scalar(
+        "SELECT MAX(updated_at) FROM scanner_patterns
+         WHERE verified = TRUE OR moderated_at IS NOT NULL",
+    )
+    .fetch_one(&state.pool)
+    .await?;
+
+    let rev = bundle::current_revision(
+        &state.pool,
+        "patterns",
+        &hash,
+        last_modified.unwrap_or_else(Utc::now),
+        &json!(entries),
+    )
+    .await?;
+
+    Ok((entries, rev))
+}
+
+/// A verified version of a pattern, current or retained.
+#[derive(sqlx::FromRow)]
+struct VersionedEntry {
+    id: Uuid,
+    version: i32,
+    examples: Option<Value>,
+    #[sqlx(flatten)]
+    entry: BundleEntry,
+}
+
+/// The version of each active pattern that the bundle serves, in bundle order.
+async fn bundled_versions(pool: &PgPool) -> Result<Vec<VersionedEntry>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, VersionedEntry>(
+        "SELECT id, version, examples, name, category, pattern, severity, replacement_hint
+         FROM scanner_patterns WHERE active = TRUE AND verified = TRUE
+         UNION ALL
+         SELECT v.pattern_id, v.version, v.examples, p.name, v.category, v.pattern, v.severity, v.replacement_hint
+         FROM scanner_pattern_versions v
+         JOIN scanner_patterns p ON p.id = v.pattern_id
+         WHERE p.active = TRUE AND v.verified = TRUE
+         ORDER BY category, name",
     )
-        .into_response()
+    .fetch_all(pool)
+    .await?;
+
+    Ok(newest_verified(rows))
+}
+
+/// Keep only the newest verified version of each pattern, in the given order.
+fn newest_verified(rows: Vec<VersionedEntry>) -> Vec<VersionedEntry> {
+    let mut newest: HashMap<Uuid, i32> = HashMap::new();
+    for row in &rows {
+        let v = newest.entry(row.id).or_insert(row.version);
+        *v = (*v).max(row.version);
+    }
+    rows.into_iter()
+        .filter(|row| newest[&row.id] == row.version)
+        .collect()
 }
 
 // ── Get one ───────────────────────────────────────────────────────────────────
 
 /// `GET /patterns/:id` — Get a single pattern by UUID.
+///
+/// Deactivated patterns are still returned, so their moderation reason stays visible.
 pub async fn get_pattern(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
 ) -> Result<Json<ScannerPattern>, RegistryError> {
     let pattern = sqlx::query_as::<_, ScannerPattern>(
-        "SELECT * FROM scanner_patterns WHERE id = $1 AND active = TRUE",
+        "SELECT * FROM scanner_patterns WHERE id = $1",
     )
     .bind(id)
     .fetch_optional(&state.pool)
@@ -181,6 +308,140 @@ pub async fn get_pattern(
     Ok(Json(pattern))
 }
 
+// ── Stats ─────────────────────────────────────────────────────────────────────
+
+/// `GET /patterns/:id/stats?days={n}` — Daily bundle downloads of a pattern.
+///
+/// Counts lag by up to [`downloads::AGGREGATE_INTERVAL`]. `days` defaults to 30
+/// (max 365); days without downloads are omitted.
+pub async fn pattern_stats(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    Query(q): Query<StatsQuery>,
+) -> Result<Json<Value>, RegistryError> {
+    let days = q.days.unwrap_or(30).clamp(1, 365);
+
+    let (name, total): (String, i64) = sqlx::query_as(
+        "SELECT name, downloads FROM scanner_patterns WHERE id = $1",
+    )
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| RegistryError::ResourceNotFound(format!("Pattern {id} not found")))?;
+
+    let daily: Vec<(chrono::NaiveDate, i64)> = sqlx::query_as(
+        "SELECT day, downloads FROM pattern_downloads_daily
+         WHERE pattern_id = $1 AND day > CURRENT_DATE - $2::INT
+         ORDER BY day",
+    )
+    .bind(id)
+    .bind(days as i32)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(json!({
+        "id": id,
+        "name": name,
+        "downloads": total,
+        "days": days,
+        "daily": daily
+            .into_iter()
+            .map(|(day, downloads)| json!({ "day": day, "downloads": downloads }))
+            .collect::<Vec<_>>(),
+    })))
+}
+
+// ── Overlaps ──────────────────────────────────────────────────────────────────
+
+/// `GET /patterns/:id/overlaps` — Verified patterns that match the same samples.
+///
+/// For reviewers deciding whether a pending pattern duplicates or is subsumed
+/// by one already in the bundle. See [`crate::pattern_overlap`].
+pub async fn pattern_overlaps(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Value>, RegistryError> {
+    let pattern = sqlx::query_as::<_, ScannerPattern>(
+        "SELECT * FROM scanner_patterns WHERE id = $1",
+    )
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| RegistryError::ResourceNotFound(format!("Pattern {id} not found")))?;
+
+    let re = pattern_screening::compile(&pattern.pattern, &state.screening)
+        .map_err(|e| RegistryError::Internal(anyhow::anyhow!("stored pattern {id} does not compile: {e}")))?;
+    let examples = pattern.examples.and_then(|v| serde_json::from_value::<PatternExamples>(v).ok());
+
+    let overlaps = find_overlaps(&state, re, examples, Some(id)).await?;
+
+    Ok(Json(json!({
+        "id": id,
+        "name": pattern.name,
+        "count": overlaps.len(),
+        "overlaps": overlaps,
+    })))
+}
+
+/// Compare `re` against every verified pattern other than `exclude`.
+async fn find_overlaps(
+    state: &AppState,
+    re: Regex,
+    examples: Option<PatternExamples>,
+    exclude: Option<Uuid>,
+) -> Result<Vec<Overlap>, RegistryError> {
+    let verified = sampled_verified_set(state).await?;
+
+    tokio::task::spawn_blocking(move || {
+        let own_samples = pattern_overlap::samples(&re, examples.as_ref());
+        let mut found = pattern_overlap::find(&re, &own_samples, &verified.patterns);
+        found.retain(|o| Some(o.id) != exclude);
+        found
+    })
+    .await
+    .map_err(|e| RegistryError::Internal(anyhow::anyhow!("overlap detection failed: {e}")))
+}
+
+/// The verified patterns compiled and sampled for the current bundle revision,
+/// re-sampled only when the revision changed.
+async fn sampled_verified_set(state: &AppState) -> Result<Arc<SampledSet>, RegistryError> {
+    // Cheap check first: most requests hit the already sampled revision
+    if let Some(rev) = bundle::latest_revision(&state.pool, "patterns").await? {
+        if let Some(sampled) = state.overlaps.get(rev.revision) {
+            return Ok(sampled);
+        }
+    }
+
+    let (_, rev) = pattern_bundle(state).await?;
+    if let Some(sampled) = state.overlaps.get(rev.revision) {
+        return Ok(sampled);
+    }
+
+    // The versions actually bundled, so a pattern under re-review is sampled
+    // at the earlier version clients still enforce
+    let verified = bundled_versions(&state.pool).await?;
+
+    let limits = state.screening.clone();
+    let revision = rev.revision;
+    let sampled = tokio::task::spawn_blocking(move || {
+        let patterns = verified
+            .into_iter()
+            .filter_map(|row| {
+                let re = pattern_screening::compile(&row.entry.pattern, &limits).ok()?;
+                let examples = row.examples.and_then(|v| serde_json::from_value::<PatternExamples>(v).ok());
+                let samples = pattern_overlap::samples(&re, examples.as_ref());
+                Some(pattern_overlap::Sampled { id: row.id, name: row.entry.name, re, samples })
+            })
+            .collect();
+        Arc::new(SampledSet { revision, patterns })
+    })
+    .await
+    .map_err(|e| RegistryError::Internal(anyhow::anyhow!("overlap sampling failed: {e}")))?;
+
+    state.overlaps.install(sampled.clone());
+    Ok(sampled)
+}
+
 // ── Create ────────────────────────────────────────────────────────────────────
 
 /// `POST /patterns` — Submit a new community scanner pattern.
@@ -188,33 +449,20 @@ pub async fn get_pattern(
 /// Requires a valid Ed25519 signature from the author's `did:sigil:` key.
 pub async fn create_pattern(
     State(state): State<Arc<AppState>>,
-    Json(req): Json<CreatePatternRequest>,
+    Json(body): Json<Value>,
 ) -> Result<(StatusCode, Json<Value>), RegistryError> {
-    // 1. Validate category
-    let valid_categories = ["secret", "pii", "credential", "financial"];
-    if !valid_categories.contains(&req.category.as_str()) {
-        return Err(RegistryError::Validation(format!(
-            "category must be one of: {}",
-            valid_categories.join(", ")
-        )));
-    }
+    let req: CreatePatternRequest = replay::parse_body(&body)?;
 
-    // 2. Validate severity
-    let severity = req.severity.as_deref().unwrap_or("high");
-    let valid_severities = ["low", "medium", "high", "critical"];
-    if !valid_severities.contains(&severity) {
-        return Err(RegistryError::Validation(format!(
-            "severity must be one of: {}",
-            valid_severities.join(", ")
-        )));
-    }
+    // 1. Validate category and severity
+    let severity = validate_category_and_severity(&req.category, req.severity.as_deref())?;
 
-    // 3. Validate the regex compiles
-    if let Err(e) = regex::Regex::new(&req.pattern) {
-        return Err(RegistryError::Validation(format!("invalid regex: {e}")));
-    }
+    // 2. Validate the regex compiles within the size and nesting limits,
+    //    and behaves as its examples claim
+    let re = pattern_screening::compile(&req.pattern, &state.screening).map_err(RegistryError::Validation)?;
+    pattern_screening::check_examples(&re, &req.should_match, &req.should_not_match)
+        .map_err(RegistryError::Validation)?;
 
-    // 4. Verify the author DID exists and fetch its public key
+    // 3. Verify the author DID exists and fetch its public key
     let author_key: Option<String> = sqlx::query_scalar(
         "SELECT public_key FROM dids WHERE did = $1 AND status = 'active'",
     )
@@ -224,12 +472,21 @@ pub async fn create_pattern(
 
     let public_key = author_key.ok_or_else(|| RegistryError::UnknownAuthor(req.author_did.clone()))?;
 
-    // 5. Verify the Ed25519 signature
+    // 4. Verify the Ed25519 signature
     let message = auth::pattern_message(&req.name, &req.category, &req.pattern, &req.author_did);
-    auth::verify_signature(&public_key, &message, &req.signature)
-        .map_err(RegistryError::InvalidSignature)?;
+    replay::verify_signed(
+        &state,
+        &public_key,
+        &req.author_did,
+        &req.signed,
+        &body,
+        "/patterns",
+        Some(&message),
+    )
+    .await?;
+    rate_limit::check_did(&state, Route::Pattern, &req.author_did).await?;
 
-    // 6. Check for duplicate name
+    // 5. Check for duplicate name
     let exists: bool = sqlx::query_scalar(
         "SELECT EXISTS(SELECT 1 FROM scanner_patterns WHERE name = $1 AND active = TRUE)",
     )
@@ -244,11 +501,30 @@ pub async fn create_pattern(
         )));
     }
 
-    // 7. Insert
+    // 6. Benchmark against the adversarial corpus — only for authenticated,
+    //    non-duplicate submissions, since it costs real CPU time
+    let pattern = req.pattern.clone();
+    let limits = state.screening.clone();
+    let (synth_re, should_match, should_not_match) = (re.clone(), req.should_match.clone(), req.should_not_match.clone());
+    let (screening, examples) = tokio::task::spawn_blocking(move || {
+        let report = pattern_screening::screen(&pattern, &limits)?;
+        Ok::<_, String>((report, pattern_screening::stored_examples(&synth_re, &should_match, &should_not_match)))
+    })
+    .await
+    .map_err(|e| RegistryError::Internal(anyhow::anyhow!("pattern screening failed: {e}")))?
+    .map_err(RegistryError::Validation)?;
+
+    // 7. Report verified patterns that match the same samples
+    let overlaps = find_overlaps(&state, re, Some(examples.clone()), None).await?;
+
+    // 8. Insert and record in the audit chain
+
+    let mut tx = state.pool.begin().await?;
+
     let id: Uuid = sqlx::query_scalar(
         "INSERT INTO scanner_patterns
-           (name, description, category, pattern, replacement_hint, severity, author_did)
-         VALUES ($1, $2, $3, $4, $5, $6, $7)
+           (name, description, category, pattern, replacement_hint, severity, author_did, examples)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
          RETURNING id",
     )
     .bind(&req.name)
@@ -258,10 +534,26 @@ pub async fn create_pattern(
     .bind(&req.replacement_hint)
     .bind(severity)
     .bind(&req.author_did)
-    .fetch_one(&state.pool)
+    .bind(json!(examples))
+    .fetch_one(&mut *tx)
+    .await?;
+
+    audit::append(
+        &mut tx,
+        state.audit_key.as_deref(),
+        "pattern.submitted",
+        &id.to_string(),
+        Some(&req.author_did),
+        json!({ "name": req.name, "category": req.category, "pattern": req.pattern, "severity": severity }),
+    )
     .await?;
 
+    tx.commit().await?;
+
     tracing::info!("New scanner pattern submitted: '{}' by {}", req.name, req.author_did);
+    if let Some(dup) = overlaps.iter().find(|o| o.likely_duplicate) {
+        tracing::info!("Pattern '{}' is a likely duplicate of '{}'", req.name, dup.name);
+    }
 
     Ok((
         StatusCode::CREATED,
@@ -270,25 +562,280 @@ pub async fn create_pattern(
             "name": req.name,
             "status": "pending_review",
             "message": "Pattern submitted. It will appear in the bundle once verified by a SIGIL maintainer.",
+            "screening": screening,
+            "examples": examples,
+            "overlaps": overlaps,
         })),
     ))
 }
 
+/// Check `category` and `severity` (default `high`), returning the severity to store.
+fn validate_category_and_severity<'a>(category: &str, severity: Option<&'a str>) -> Result<&'a str, RegistryError> {
+    let valid_categories = ["secret", "pii", "credential", "financial"];
+    if !valid_categories.contains(&category) {
+        return Err(RegistryError::Validation(format!(
+            "category must be one of: {}",
+            valid_categories.join(", ")
+        )));
+    }
+
+    let severity = severity.unwrap_or("high");
+    let valid_severities = ["low", "medium", "high", "critical"];
+    if !valid_severities.contains(&severity) {
+        return Err(RegistryError::Validation(format!(
+            "severity must be one of: {}",
+            valid_severities.join(", ")
+        )));
+    }
+
+    Ok(severity)
+}
+
+// ── Versions ──────────────────────────────────────────────────────────────────
+
+/// Fields compared between consecutive versions.
+const VERSIONED_FIELDS: [&str; 6] = ["description", "category", "pattern", "replacement_hint", "severity", "examples"];
+
+/// `PUT /patterns/:id` — Submit a new version of a pattern.
+///
+/// Only the original author may edit, signing with their `did:sigil:` key.
+/// The new version goes through the same screening as a submission and
+/// returns to `pending` review; until it is approved the bundle keeps serving
+/// the last verified version. `version` must be the current version plus one,
+/// so concurrent edits conflict instead of overwriting each other.
+pub async fn update_pattern(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<Value>,
+) -> Result<Json<Value>, RegistryError> {
+    let req: UpdatePatternRequest = replay::parse_body(&body)?;
+
+    // 1. The pattern must exist, be active and have an author
+    let current = sqlx::query_as::<_, ScannerPattern>(
+        "SELECT * FROM scanner_patterns WHERE id = $1 AND active = TRUE",
+    )
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| RegistryError::ResourceNotFound(format!("Pattern {id} not found")))?;
+
+    let author_did = current.author_did.clone().ok_or_else(|| {
+        RegistryError::Forbidden("maintainer-seeded patterns cannot be edited through the API".into())
+    })?;
+
+    check_next_version(id, current.version, req.version)?;
+
+    // 2. Validate fields, regex and examples exactly as on submission
+    let severity = validate_category_and_severity(&req.category, req.severity.as_deref())?;
+    let re = pattern_screening::compile(&req.pattern, &state.screening).map_err(RegistryError::Validation)?;
+    pattern_screening::check_examples(&re, &req.should_match, &req.should_not_match)
+        .map_err(RegistryError::Validation)?;
+
+    // 3. Verify the author's signature
+    let author_key: Option<String> = sqlx::query_scalar(
+        "SELECT public_key FROM dids WHERE did = $1 AND status = 'active'",
+    )
+    .bind(&author_did)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    let public_key = author_key.ok_or_else(|| RegistryError::UnknownAuthor(author_did.clone()))?;
+
+    let message = auth::pattern_update_message(&id.to_string(), req.version, &req.category, &req.pattern, &author_did);
+    replay::verify_signed(
+        &state,
+        &public_key,
+        &author_did,
+        &req.signed,
+        &body,
+        &format!("/patterns/{id}"),
+        Some(&message),
+    )
+    .await?;
+    rate_limit::check_did(&state, Route::Pattern, &author_did).await?;
+
+    // 4. Benchmark and compare against the verified set
+    let pattern = req.pattern.clone();
+    let limits = state.screening.clone();
+    let (synth_re, should_match, should_not_match) = (re.clone(), req.should_match.clone(), req.should_not_match.clone());
+    let (screening, examples) = tokio::task::spawn_blocking(move || {
+        let report = pattern_screening::screen(&pattern, &limits)?;
+        Ok::<_, String>((report, pattern_screening::stored_examples(&synth_re, &should_match, &should_not_match)))
+    })
+    .await
+    .map_err(|e| RegistryError::Internal(anyhow::anyhow!("pattern screening failed: {e}")))?
+    .map_err(RegistryError::Validation)?;
+
+    let overlaps = find_overlaps(&state, re, Some(examples.clone()), Some(id)).await?;
+
+    // 5. Retain the current version, replace it and record in the audit chain
+    let mut tx = state.pool.begin().await?;
+
+    let retained = sqlx::query(
+        "INSERT INTO scanner_pattern_versions
+           (pattern_id, version, description, category, pattern, replacement_hint, severity, examples,
+            verified, review_status, moderated_by, moderated_at, moderation_reason, created_at)
+         SELECT id, version, description, category, pattern, replacement_hint, severity, examples,
+                verified, review_status, moderated_by, moderated_at, moderation_reason, version_created_at
+         FROM scanner_patterns
+         WHERE id = $1 AND version = $2 AND active = TRUE
+         ON CONFLICT (pattern_id, version) DO NOTHING",
+    )
+    .bind(id)
+    .bind(current.version)
+    .execute(&mut *tx)
+    .await?;
+
+    if retained.rows_affected() == 0 {
+        return Err(RegistryError::Duplicate(format!(
+            "Pattern {id} was edited concurrently; fetch it and retry"
+        )));
+    }
+
+    sqlx::query(
+        "UPDATE scanner_patterns
+         SET version = $2, description = $3, category = $4, pattern = $5, replacement_hint = $6,
+             severity = $7, examples = $8,
+             verified = FALSE, review_status = 'pending',
+             moderated_by = NULL, moderated_at = NULL, moderation_reason = NULL,
+             version_created_at = NOW(), updated_at = NOW()
+         WHERE id = $1",
+    )
+    .bind(id)
+    .bind(req.version)
+    .bind(&req.description)
+    .bind(&req.category)
+    .bind(&req.pattern)
+    .bind(&req.replacement_hint)
+    .bind(severity)
+    .bind(json!(examples))
+    .execute(&mut *tx)
+    .await?;
+
+    audit::append(
+        &mut tx,
+        state.audit_key.as_deref(),
+        "pattern.updated",
+        &id.to_string(),
+        Some(&author_did),
+        json!({
+            "name": current.name,
+            "version": req.version,
+            "category": req.category,
+            "pattern": req.pattern,
+            "severity": severity,
+        }),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    tracing::info!("Scanner pattern '{}' updated to version {} by {author_did}", current.name, req.version);
+
+    // Record the bundled set (which may fall back to an earlier version) right away
+    if let Err(e) = pattern_bundle(&state).await {
+        tracing::warn!("pattern bundle revision refresh failed: {e}");
+    }
+
+    Ok(Json(json!({
+        "id": id,
+        "name": current.name,
+        "version": req.version,
+        "status": "pending_review",
+        "message": "New version submitted. The bundle serves the last verified version until this one is approved.",
+        "screening": screening,
+        "examples": examples,
+        "overlaps": overlaps,
+    })))
+}
+
+/// `GET /patterns/:id/versions` — Every version of a pattern, oldest first.
+///
+/// Each version after the first carries a `diff` of the fields that changed,
+/// as `{ field: { "from": .., "to": .. } }`.
+pub async fn pattern_versions(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Value>, RegistryError> {
+    let name: String = sqlx::query_scalar("SELECT name FROM scanner_patterns WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| RegistryError::ResourceNotFound(format!("Pattern {id} not found")))?;
+
+    let versions = sqlx::query_as::<_, PatternVersion>(
+        "SELECT version, description, category, pattern, replacement_hint, severity, examples,
+                verified, review_status, moderated_by, moderated_at, moderation_reason,
+                created_at, superseded_at
+         FROM scanner_pattern_versions WHERE pattern_id = $1
+         UNION ALL
+         SELECT version, description, category, pattern, replacement_hint, severity, examples,
+                verified, review_status, moderated_by, moderated_at, moderation_reason,
+                version_created_at, NULL
+         FROM scanner_patterns WHERE id = $1
+         ORDER BY version",
+    )
+    .bind(id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let out = version_history(versions);
+
+    Ok(Json(json!({
+        "id": id,
+        "name": name,
+        "count": out.len(),
+        "versions": out,
+    })))
+}
+
+/// Reject an edit unless it is numbered `current + 1`.
+fn check_next_version(id: Uuid, current: i32, requested: i32) -> Result<(), RegistryError> {
+    if requested != current + 1 {
+        return Err(RegistryError::Duplicate(format!(
+            "Pattern {id} is at version {current}; the next version is {}",
+            current + 1
+        )));
+    }
+    Ok(())
+}
+
+/// Serialize `versions` (oldest first), giving each one after the first a
+/// `diff` of the [`VERSIONED_FIELDS`] that changed since its predecessor.
+fn version_history(versions: Vec<PatternVersion>) -> Vec<Value> {
+    let mut previous: Option<Value> = None;
+    let mut out = Vec::with_capacity(versions.len());
+    for version in versions {
+        let mut entry = json!(version);
+        if let Some(prev) = &previous {
+            let diff: serde_json::Map<String, Value> = VERSIONED_FIELDS
+                .iter()
+                .filter(|f| prev[**f] != entry[**f])
+                .map(|f| (f.to_string(), json!({ "from": prev[*f], "to": entry[*f] })))
+                .collect();
+            entry["diff"] = Value::Object(diff);
+        }
+        previous = Some(entry.clone());
+        out.push(entry);
+    }
+    out
+}
+
 // ── Vote ──────────────────────────────────────────────────────────────────────
 
 /// `POST /patterns/:id/vote` — Vote on a scanner pattern.
 ///
 /// Requires a valid Ed25519 signature from the voter's `did:sigil:` key.
-/// Each DID can only vote once per pattern.
+/// Each DID holds at most one vote per pattern; voting the other way changes
+/// it and `"vote": "retract"` withdraws it, both only with a v1 signature.
 pub async fn vote_pattern(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
-    Json(req): Json<VoteRequest>,
+    Json(body): Json<Value>,
 ) -> Result<Json<Value>, RegistryError> {
-    // Validate vote direction
-    if req.vote != "up" && req.vote != "down" {
-        return Err(RegistryError::InvalidVote);
-    }
+    let req: VoteRequest = replay::parse_body(&body)?;
+
+    let ballot = Ballot::parse(&req.vote).ok_or(RegistryError::InvalidVote)?;
 
     // Verify the voter DID exists
     let voter_key: Option<String> = sqlx::query_scalar(
@@ -300,48 +847,107 @@ pub async fn vote_pattern(
 
     let public_key = voter_key.ok_or_else(|| RegistryError::UnknownAuthor(req.voter_did.clone()))?;
 
-    // Verify signature
+    // Verify signature — a retraction has no v0 form, see `replay`
     let message = auth::vote_message("pattern", &id.to_string(), &req.vote, &req.voter_did);
-    auth::verify_signature(&public_key, &message, &req.signature)
-        .map_err(RegistryError::InvalidSignature)?;
-
-    // Check pattern exists
-    let exists: bool = sqlx::query_scalar(
-        "SELECT EXISTS(SELECT 1 FROM scanner_patterns WHERE id = $1 AND active = TRUE)",
+    let v0_message = (ballot != Ballot::Retract).then_some(message.as_str());
+    replay::verify_signed(
+        &state,
+        &public_key,
+        &req.voter_did,
+        &req.signed,
+        &body,
+        &format!("/patterns/{id}/vote"),
+        v0_message,
     )
-    .bind(id)
-    .fetch_one(&state.pool)
     .await?;
-
-    if !exists {
-        return Err(RegistryError::ResourceNotFound(format!("Pattern {id} not found")));
-    }
-
-    // Record vote (unique constraint prevents double-voting)
-    let insert_result = sqlx::query(
-        "INSERT INTO registry_votes (voter_did, target_type, target_id, vote)
-         VALUES ($1, 'pattern', $2, $3)
-         ON CONFLICT (voter_did, target_type, target_id) DO NOTHING
-         RETURNING id",
+    rate_limit::check_did(&state, Route::Vote, &req.voter_did).await?;
+
+    let recorded = votes::record(
+        &state,
+        Target::Pattern,
+        id,
+        &req.voter_did,
+        ballot,
+        req.signed.is_v1(),
     )
-    .bind(&req.voter_did)
-    .bind(id)
-    .bind(&req.vote)
-    .execute(&state.pool)
     .await?;
 
-    if insert_result.rows_affected() == 0 {
-        return Err(RegistryError::AlreadyVoted);
+    Ok(Json(json!({
+        "id": id,
+        "vote": req.vote,
+        "previous": recorded.previous,
+        "votes_up": recorded.votes_up,
+        "votes_down": recorded.votes_down,
+        "weight": recorded.weight,
+        "score": recorded.score,
+        "recorded": true,
+    })))
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(n: i32, pattern: &str, severity: &str) -> PatternVersion {
+        PatternVersion {
+            version: n,
+            description: None,
+            category: "secret".into(),
+            pattern: pattern.into(),
+            replacement_hint: None,
+            severity: severity.into(),
+            examples: None,
+            verified: n == 1,
+            review_status: if n == 1 { "approved" } else { "pending" }.into(),
+            moderated_by: None,
+            moderated_at: None,
+            moderation_reason: None,
+            created_at: Utc::now(),
+            superseded_at: None,
+        }
     }
 
-    // Update the vote counter on the pattern
-    let col = if req.vote == "up" { "votes_up" } else { "votes_down" };
-    sqlx::query(&format!(
-        "UPDATE scanner_patterns SET {col} = {col} + 1, updated_at = NOW() WHERE id = $1"
-    ))
-    .bind(id)
-    .execute(&state.pool)
-    .await?;
+    #[test]
+    fn bundle_falls_back_to_the_newest_verified_version() {
+        let row = |id: u128, version: i32| VersionedEntry {
+            id: Uuid::from_u128(id),
+            version,
+            examples: None,
+            entry: BundleEntry {
+                name: format!("p{id}"),
+                category: "secret".into(),
+                pattern: format!("p{id}v{version}"),
+                severity: "high".into(),
+                replacement_hint: None,
+            },
+        };
+        // 1 is verified at version 3; 2 awaits review, verified at 1 and 2
+        let bundled = newest_verified(vec![row(1, 3), row(1, 1), row(2, 1), row(2, 2)]);
+        let patterns: Vec<&str> = bundled.iter().map(|row| row.entry.pattern.as_str()).collect();
+        assert_eq!(patterns, ["p1v3", "p2v2"]);
+    }
 
-    Ok(Json(json!({ "id": id, "vote": req.vote, "recorded": true })))
+    #[test]
+    fn edits_must_take_the_next_version() {
+        let id = Uuid::nil();
+        assert!(check_next_version(id, 1, 2).is_ok());
+        for requested in [1, 3, 0, -1] {
+            assert!(matches!(check_next_version(id, 1, requested), Err(RegistryError::Duplicate(_))));
+        }
+    }
+
+    #[test]
+    fn versions_diff_only_changed_fields() {
+        let history = version_history(vec![
+            version(1, "a_[0-9]+", "high"),
+            version(2, "a_[0-9]{8}", "high"),
+            version(3, "a_[0-9]{8}", "medium"),
+        ]);
+        assert!(history[0].get("diff").is_none());
+        // Review fields change too, but are not part of a version's content
+        assert_eq!(history[1]["diff"], json!({ "pattern": { "from": "a_[0-9]+", "to": "a_[0-9]{8}" } }));
+        assert_eq!(history[2]["diff"], json!({ "severity": { "from": "high", "to": "medium" } }));
+    }
 }
@@ -7,21 +7,28 @@
 //! ## Endpoints
 //!
 //! - `GET  /policies`            — List policies (filterable by tool_name/risk/verified)
+//! - `GET  /policies/bundle`     — One resolved verified policy per tool (for SDK use)
 //! - `GET  /policies/:id`        — Get a single policy
 //! - `POST /policies`            — Submit a policy (requires Ed25519 signature)
 //! - `POST /policies/:id/vote`   — Vote on a policy (requires Ed25519 signature)
 
 use crate::{
-    auth,
+    audit, auth,
+    bundle::{self, BundleRevision},
     db::AppState,
     error::RegistryError,
-    models::{CreatePolicyRequest, PolicyQuery, SecurityPolicy, VoteRequest},
+    models::{CreatePolicyRequest, PolicyBundleEntry, PolicyQuery, SecurityPolicy, VoteRequest},
+    rate_limit::{self, Route},
+    replay,
+    votes::{self, Ballot, Target},
 };
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
+use chrono::{DateTime, Utc};
 use serde_json::{json, Value};
 use std::sync::Arc;
 use uuid::Uuid;
@@ -29,6 +36,9 @@ use uuid::Uuid;
 // ── List ──────────────────────────────────────────────────────────────────────
 
 /// `GET /policies` — List security policies, optionally filtered.
+///
+/// Ordered by reputation-weighted vote `score` (verified policies first where
+/// the filter mixes both).
 pub async fn list_policies(
     State(state): State<Arc<AppState>>,
     Query(q): Query<PolicyQuery>,
@@ -41,7 +51,7 @@ pub async fn list_policies(
         (Some(tool), None, None) => sqlx::query_as::<_, SecurityPolicy>(
             "SELECT * FROM security_policies
              WHERE active = TRUE AND tool_name = $1
-             ORDER BY verified DESC, votes_up DESC LIMIT $2 OFFSET $3",
+             ORDER BY verified DESC, score DESC, votes_up DESC LIMIT $2 OFFSET $3",
         )
         .bind(tool)
         .bind(limit)
@@ -52,7 +62,7 @@ pub async fn list_policies(
         (None, Some(risk), None) => sqlx::query_as::<_, SecurityPolicy>(
             "SELECT * FROM security_policies
              WHERE active = TRUE AND risk_level = $1
-             ORDER BY verified DESC, votes_up DESC LIMIT $2 OFFSET $3",
+             ORDER BY verified DESC, score DESC, votes_up DESC LIMIT $2 OFFSET $3",
         )
         .bind(risk)
         .bind(limit)
@@ -63,7 +73,7 @@ pub async fn list_policies(
         (None, None, Some(v)) => sqlx::query_as::<_, SecurityPolicy>(
             "SELECT * FROM security_policies
              WHERE active = TRUE AND verified = $1
-             ORDER BY votes_up DESC LIMIT $2 OFFSET $3",
+             ORDER BY score DESC, votes_up DESC LIMIT $2 OFFSET $3",
         )
         .bind(v)
         .bind(limit)
@@ -74,7 +84,7 @@ pub async fn list_policies(
         (Some(tool), None, Some(v)) => sqlx::query_as::<_, SecurityPolicy>(
             "SELECT * FROM security_policies
              WHERE active = TRUE AND tool_name = $1 AND verified = $2
-             ORDER BY votes_up DESC LIMIT $3 OFFSET $4",
+             ORDER BY score DESC, votes_up DESC LIMIT $3 OFFSET $4",
         )
         .bind(tool)
         .bind(v)
@@ -86,7 +96,7 @@ pub async fn list_policies(
         _ => sqlx::query_as::<_, SecurityPolicy>(
             "SELECT * FROM security_policies
              WHERE active = TRUE
-             ORDER BY verified DESC, votes_up DESC LIMIT $1 OFFSET $2",
+             ORDER BY verified DESC, score DESC, votes_up DESC LIMIT $1 OFFSET $2",
         )
         .bind(limit)
         .bind(offset)
@@ -101,15 +111,100 @@ pub async fn list_policies(
     })))
 }
 
+// ── Bundle ────────────────────────────────────────────────────────────────────
+
+/// `GET /policies/bundle` — One resolved policy per tool, for SDK consumption.
+///
+/// Only active, verified policies take part. When several cover the same
+/// `tool_name`, the winner is chosen by, in order:
+///
+/// 1. highest `score` (reputation-weighted votes, see [`crate::reputation`])
+/// 2. most `votes_up`
+/// 3. most recently submitted (`created_at`)
+/// 4. lowest `id`, so the choice is deterministic
+///
+/// Cache headers, signature, `revision` and `ETag` / `304` semantics are the
+/// same as for `GET /patterns/bundle`.
+pub async fn get_policy_bundle(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Response {
+    let (bundle, rev) = match policy_bundle(&state).await {
+        Ok(b) => b,
+        Err(e) => {
+            tracing::error!("get_policy_bundle DB error: {e}");
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "database error" }))).into_response();
+        }
+    };
+
+    let body = json!({
+        "version": "1",
+        "revision": rev.revision,
+        "generated_at": rev.created_at,
+        "count": bundle.len(),
+        "policies": bundle,
+    });
+
+    bundle::respond(&state, &headers, &rev, &rev.etag(), &body)
+}
+
+/// The resolved verified policy set and its bundle revision.
+pub(crate) async fn policy_bundle(
+    state: &AppState,
+) -> anyhow::Result<(Vec<PolicyBundleEntry>, BundleRevision)> {
+    let policies = sqlx::query_as::<_, SecurityPolicy>(
+        "SELECT DISTINCT ON (tool_name) * FROM security_policies
+         WHERE active = TRUE AND verified = TRUE
+         ORDER BY tool_name, score DESC, votes_up DESC, created_at DESC, id",
+    )
+    .fetch_all(&state.pool)
+    .await?;
+
+    let entries: Vec<PolicyBundleEntry> = policies
+        .into_iter()
+        .map(|p| PolicyBundleEntry {
+            id: p.id,
+            tool_name: p.tool_name,
+            risk_level: p.risk_level,
+            requires_trust: p.requires_trust,
+            requires_confirmation: p.requires_confirmation,
+            rationale: p.rationale,
+        })
+        .collect();
+
+    let hash = bundle::content_hash(&entries)?;
+
+    // Votes can change which policy wins, so every updated_at counts here
+    let last_modified: Option<DateTime<Utc>> = sqlx::query_scalar(
+        "SELECT MAX(updated_at) FROM security_policies
+         WHERE verified = TRUE OR moderated_at IS NOT NULL",
+    )
+    .fetch_one(&state.pool)
+    .await?;
+
+    let rev = bundle::current_revision(
+        &state.pool,
+        "policies",
+        &hash,
+        last_modified.unwrap_or_else(Utc::now),
+        &json!(entries),
+    )
+    .await?;
+
+    Ok((entries, rev))
+}
+
 // ── Get one ───────────────────────────────────────────────────────────────────
 
 /// `GET /policies/:id` — Get a single security policy by UUID.
+///
+/// Deactivated policies are still returned, so their moderation reason stays visible.
 pub async fn get_policy(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
 ) -> Result<Json<SecurityPolicy>, RegistryError> {
     let policy = sqlx::query_as::<_, SecurityPolicy>(
-        "SELECT * FROM security_policies WHERE id = $1 AND active = TRUE",
+        "SELECT * FROM security_policies WHERE id = $1",
     )
     .bind(id)
     .fetch_optional(&state.pool)
@@ -126,8 +221,10 @@ pub async fn get_policy(
 /// Requires a valid Ed25519 signature from the author's `did:sigil:` key.
 pub async fn create_policy(
     State(state): State<Arc<AppState>>,
-    Json(req): Json<CreatePolicyRequest>,
+    Json(body): Json<Value>,
 ) -> Result<(StatusCode, Json<Value>), RegistryError> {
+    let req: CreatePolicyRequest = replay::parse_body(&body)?;
+
     // 1. Validate risk level
     let valid_risks = ["low", "medium", "high", "critical"];
     if !valid_risks.contains(&req.risk_level.as_str()) {
@@ -168,10 +265,21 @@ pub async fn create_policy(
         &req.requires_trust,
         &req.author_did,
     );
-    auth::verify_signature(&public_key, &message, &req.signature)
-        .map_err(RegistryError::InvalidSignature)?;
+    replay::verify_signed(
+        &state,
+        &public_key,
+        &req.author_did,
+        &req.signed,
+        &body,
+        "/policies",
+        Some(&message),
+    )
+    .await?;
+    rate_limit::check_did(&state, Route::Policy, &req.author_did).await?;
 
     // 6. Insert (allow multiple policies per tool — community votes surface the best one)
+    let mut tx = state.pool.begin().await?;
+
     let id: Uuid = sqlx::query_scalar(
         "INSERT INTO security_policies
            (tool_name, risk_level, requires_trust, requires_confirmation, rationale, author_did)
@@ -184,9 +292,26 @@ pub async fn create_policy(
     .bind(req.requires_confirmation.unwrap_or(false))
     .bind(&req.rationale)
     .bind(&req.author_did)
-    .fetch_one(&state.pool)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    audit::append(
+        &mut tx,
+        state.audit_key.as_deref(),
+        "policy.submitted",
+        &id.to_string(),
+        Some(&req.author_did),
+        json!({
+            "tool_name": req.tool_name,
+            "risk_level": req.risk_level,
+            "requires_trust": req.requires_trust,
+            "requires_confirmation": req.requires_confirmation.unwrap_or(false),
+        }),
+    )
     .await?;
 
+    tx.commit().await?;
+
     tracing::info!(
         "New security policy submitted: '{}' (risk={}) by {}",
         req.tool_name, req.risk_level, req.author_did
@@ -207,16 +332,17 @@ pub async fn create_policy(
 
 /// `POST /policies/:id/vote` — Vote on a security policy.
 ///
-/// Each DID can only vote once per policy. Requires Ed25519 signature.
+/// Each DID holds at most one vote per policy; voting the other way changes
+/// it and `"vote": "retract"` withdraws it, both only with a v1 (nonce-bearing)
+/// signature.
 pub async fn vote_policy(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
-    Json(req): Json<VoteRequest>,
+    Json(body): Json<Value>,
 ) -> Result<Json<Value>, RegistryError> {
-    // Validate vote direction
-    if req.vote != "up" && req.vote != "down" {
-        return Err(RegistryError::InvalidVote);
-    }
+    let req: VoteRequest = replay::parse_body(&body)?;
+
+    let ballot = Ballot::parse(&req.vote).ok_or(RegistryError::InvalidVote)?;
 
     // Verify the voter DID exists
     let voter_key: Option<String> = sqlx::query_scalar(
@@ -228,47 +354,39 @@ pub async fn vote_policy(
 
     let public_key = voter_key.ok_or_else(|| RegistryError::UnknownAuthor(req.voter_did.clone()))?;
 
-    // Verify signature
+    // Verify signature — a retraction has no v0 form, see `replay`
     let message = auth::vote_message("policy", &id.to_string(), &req.vote, &req.voter_did);
-    auth::verify_signature(&public_key, &message, &req.signature)
-        .map_err(RegistryError::InvalidSignature)?;
-
-    // Check policy exists
-    let exists: bool = sqlx::query_scalar(
-        "SELECT EXISTS(SELECT 1 FROM security_policies WHERE id = $1 AND active = TRUE)",
+    let v0_message = (ballot != Ballot::Retract).then_some(message.as_str());
+    replay::verify_signed(
+        &state,
+        &public_key,
+        &req.voter_did,
+        &req.signed,
+        &body,
+        &format!("/policies/{id}/vote"),
+        v0_message,
     )
-    .bind(id)
-    .fetch_one(&state.pool)
     .await?;
-
-    if !exists {
-        return Err(RegistryError::ResourceNotFound(format!("Policy {id} not found")));
-    }
-
-    // Record vote (unique constraint prevents double-voting)
-    let insert_result = sqlx::query(
-        "INSERT INTO registry_votes (voter_did, target_type, target_id, vote)
-         VALUES ($1, 'policy', $2, $3)
-         ON CONFLICT (voter_did, target_type, target_id) DO NOTHING",
+    rate_limit::check_did(&state, Route::Vote, &req.voter_did).await?;
+
+    let recorded = votes::record(
+        &state,
+        Target::Policy,
+        id,
+        &req.voter_did,
+        ballot,
+        req.signed.is_v1(),
     )
-    .bind(&req.voter_did)
-    .bind(id)
-    .bind(&req.vote)
-    .execute(&state.pool)
     .await?;
 
-    if insert_result.rows_affected() == 0 {
-        return Err(RegistryError::AlreadyVoted);
-    }
-
-    // Update vote counter
-    let col = if req.vote == "up" { "votes_up" } else { "votes_down" };
-    sqlx::query(&format!(
-        "UPDATE security_policies SET {col} = {col} + 1, updated_at = NOW() WHERE id = $1"
-    ))
-    .bind(id)
-    .execute(&state.pool)
-    .await?;
-
-    Ok(Json(json!({ "id": id, "vote": req.vote, "recorded": true })))
+    Ok(Json(json!({
+        "id": id,
+        "vote": req.vote,
+        "previous": recorded.previous,
+        "votes_up": recorded.votes_up,
+        "votes_down": recorded.votes_down,
+        "weight": recorded.weight,
+        "score": recorded.score,
+        "recorded": true,
+    })))
 }
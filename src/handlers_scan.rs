@@ -0,0 +1,97 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Handler for scanning text server-side with the verified pattern set.
+//!
+//! ## Endpoints
+//!
+//! - `POST /scan` — Match spans and redacted text for a submitted string
+//!
+//! The submitted text is neither logged nor stored; nothing in this module may
+//! put it (or any part of it) into a log line or an error message.
+
+use crate::{
+    bundle,
+    db::AppState,
+    error::RegistryError,
+    handlers_patterns,
+    models::ScanRequest,
+    scanner::{self, CompiledBundle},
+};
+use axum::{extract::State, Json};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// `POST /scan` — Run the verified bundle over `text`.
+///
+/// Returns every match as a byte span with the pattern's name, category and
+/// severity, plus the text with matches replaced by their `replacement_hint`.
+/// The body is capped at [`scanner::MAX_BODY_BYTES`] and `text` at
+/// [`scanner::MAX_TEXT_BYTES`]; larger requests get `413 Payload Too Large`.
+///
+/// If the current verified set fails to compile, the last compiled revision
+/// keeps serving; with none, the scan fails with `500` instead of reporting
+/// no matches.
+pub async fn scan(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ScanRequest>,
+) -> Result<Json<Value>, RegistryError> {
+    if req.text.len() > scanner::MAX_TEXT_BYTES {
+        return Err(RegistryError::PayloadTooLarge(format!(
+            "text exceeds {} bytes",
+            scanner::MAX_TEXT_BYTES
+        )));
+    }
+
+    let compiled = compiled_bundle(&state).await?;
+    let revision = compiled.revision;
+
+    let result = tokio::task::spawn_blocking(move || compiled.scan(&req.text))
+        .await
+        .map_err(|e| RegistryError::Internal(anyhow::anyhow!("scan failed: {e}")))?;
+
+    Ok(Json(json!({
+        "revision": revision,
+        "count": result.matches.len(),
+        "truncated": result.truncated,
+        "matches": result.matches,
+        "redacted": result.redacted,
+    })))
+}
+
+/// The compiled bundle for the current revision, recompiling it if the revision changed.
+async fn compiled_bundle(state: &AppState) -> Result<Arc<CompiledBundle>, RegistryError> {
+    // Cheap check first: most scans hit the already compiled revision
+    if let Some(rev) = bundle::latest_revision(&state.pool, "patterns").await? {
+        if let Some(compiled) = state.scanner.get(rev.revision) {
+            return Ok(compiled);
+        }
+    }
+
+    let (entries, rev) = handlers_patterns::pattern_bundle(state).await?;
+    if let Some(compiled) = state.scanner.get(rev.revision) {
+        return Ok(compiled);
+    }
+
+    let revision = rev.revision;
+    let compiled = tokio::task::spawn_blocking(move || CompiledBundle::compile(revision, entries))
+        .await
+        .map_err(|e| RegistryError::Internal(anyhow::anyhow!("scanner compilation failed: {e}")))?;
+
+    match compiled {
+        Ok(compiled) => {
+            let compiled = Arc::new(compiled);
+            state.scanner.install(compiled.clone());
+            Ok(compiled)
+        }
+        Err(e) => {
+            tracing::error!("scanner: pattern set of revision {revision} failed to compile: {e}");
+            // Keep scanning with the last good set (its revision is in the
+            // response); without one, fail rather than report no matches
+            state.scanner.latest().ok_or_else(|| {
+                RegistryError::Internal(anyhow::anyhow!("the verified pattern set failed to compile"))
+            })
+        }
+    }
+}
@@ -0,0 +1,180 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! RFC 8785 JSON Canonicalization Scheme (JCS).
+//!
+//! Signed request bodies are verified over their canonical form, so clients in
+//! any language produce the same bytes as long as they follow RFC 8785:
+//!
+//! - no insignificant whitespace,
+//! - object members sorted by the UTF-16 code units of their names,
+//! - strings escaped as ECMAScript `JSON.stringify` does (only `"`, `\` and
+//!   control characters; everything else is written as UTF-8), and
+//! - numbers formatted as ECMAScript `Number.prototype.toString` formats
+//!   IEEE 754 doubles.
+//!
+//! The vectors in `test-vectors/jcs.json` are checked by the tests below and
+//! are meant for client implementations too.
+
+use serde_json::{Number, Value};
+use std::fmt::Write;
+
+/// Integers up to this magnitude are exact as doubles and print as-is.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Serialise `value` in RFC 8785 canonical form.
+pub fn canonicalize(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value);
+    out
+}
+
+fn write_value(out: &mut String, value: &Value) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => out.push_str(&number(n)),
+        Value::String(s) => write_string(out, s),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(out, item);
+            }
+            out.push(']');
+        }
+        Value::Object(members) => {
+            let mut members: Vec<_> = members.iter().collect();
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (i, (name, member)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(out, name);
+                out.push(':');
+                write_value(out, member);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{08}' => out.push_str("\\b"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\u{0C}' => out.push_str("\\f"),
+            '\r' => out.push_str("\\r"),
+            c if c < ' ' => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// A JSON number as ECMAScript prints the nearest double.
+fn number(n: &Number) -> String {
+    if let Some(i) = n.as_i64().filter(|i| i.unsigned_abs() <= MAX_SAFE_INTEGER) {
+        return i.to_string();
+    }
+    if let Some(u) = n.as_u64().filter(|u| *u <= MAX_SAFE_INTEGER) {
+        return u.to_string();
+    }
+    // serde_json rejects NaN and infinities, so every Number has a finite f64
+    es_number(n.as_f64().unwrap_or_default())
+}
+
+/// ECMAScript `Number::toString` (ECMA-262 §6.1.6.1.20) for a finite double.
+fn es_number(f: f64) -> String {
+    if f == 0.0 {
+        return "0".into();
+    }
+
+    // `{:e}` yields the shortest digits that round-trip, e.g. "-1.2345e-7"
+    let sci = format!("{:e}", f.abs());
+    let (mantissa, exponent) = sci.split_once('e').unwrap_or((&sci, "0"));
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+
+    // Value = 0.d1d2…dk × 10^n
+    let k = digits.len() as i32;
+    let n = exponent + 1;
+
+    let mut out = String::new();
+    if f < 0.0 {
+        out.push('-');
+    }
+    if k <= n && n <= 21 {
+        out.push_str(&digits);
+        out.extend(std::iter::repeat_n('0', (n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        out.push_str(&digits[..n as usize]);
+        out.push('.');
+        out.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        out.push_str("0.");
+        out.extend(std::iter::repeat_n('0', (-n) as usize));
+        out.push_str(&digits);
+    } else {
+        out.push_str(&digits[..1]);
+        if k > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        let _ = write!(out, "e{}{}", if n > 0 { "+" } else { "-" }, (n - 1).abs());
+    }
+    out
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(serde::Deserialize)]
+    struct Vector {
+        name: String,
+        /// JSON text, kept as a string so escapes and number spellings survive
+        input: String,
+        canonical: String,
+    }
+
+    #[test]
+    fn shipped_vectors() {
+        let vectors: Vec<Vector> = serde_json::from_str(include_str!("../test-vectors/jcs.json")).unwrap();
+        assert!(!vectors.is_empty());
+        for v in vectors {
+            let value: Value = serde_json::from_str(&v.input).unwrap();
+            assert_eq!(canonicalize(&value), v.canonical, "vector '{}'", v.name);
+        }
+    }
+
+    #[test]
+    fn numbers_follow_ecmascript() {
+        for (f, expected) in [
+            (1e21, "1e+21"),
+            (1e20, "100000000000000000000"),
+            (123.456, "123.456"),
+            (0.000001, "0.000001"),
+            (1e-7, "1e-7"),
+            (-1.5e-9, "-1.5e-9"),
+            (5e-324, "5e-324"),
+            (1.7976931348623157e308, "1.7976931348623157e+308"),
+            (-0.0, "0"),
+        ] {
+            assert_eq!(es_number(f), expected, "{f:e}");
+        }
+    }
+}
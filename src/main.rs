@@ -7,35 +7,122 @@
 //! ## DID Endpoints
 //!
 //! - `GET  /health`             — Health check
+//! - `GET  /.well-known/sigil-registry-keys` — Public keys that sign served bundles
 //! - `GET  /resolve/{did}`      — Resolve a DID to its public key + metadata
-//! - `POST /register`           — Register a new DID
-//! - `POST /revoke/{did}`       — Revoke a DID
+//! - `GET  /resolve/{did}/history` — Lifecycle event timeline for a DID
+//! - `GET  /resolve/{did}/reputation` — Reputation score that weights the DID's votes
+//! - `GET  /1.0/identifiers/{did}` — W3C DID Resolution result (Universal Resolver compatible)
+//! - `GET  /register/nonce`     — Issue a single-use nonce for a registration proof
+//! - `POST /register`           — Register a new DID (requires proof of key possession)
+//! - `POST /revoke/{did}`       — Revoke a DID (requires DID signature or `revoke`-scoped key)
+//! - `POST /rotate/{did}`       — Rotate a DID's key (signed by current and new key)
+//! - `POST /label/{did}`        — Change a DID's label (requires DID signature or `register`-scoped key)
+//!
+//! ## Namespace Endpoints
+//!
+//! - `GET  /namespaces`                 — List claimed namespaces
+//! - `POST /namespaces`                 — Claim a namespace (signed by the controller DID)
+//! - `GET  /namespaces/:name`           — Namespace, controller and delegates
+//! - `GET  /namespaces/:name/dids`      — DIDs registered in a namespace
+//! - `POST /namespaces/:name/transfer`  — Transfer control to another DID
+//! - `POST /namespaces/:name/delegates` — Grant or revoke a delegated sub-controller
 //!
 //! ## Scanner Pattern Endpoints
 //!
-//! - `GET  /patterns`           — List community patterns (filterable by category/verified)
-//! - `GET  /patterns/bundle`    — Compiled bundle of verified patterns (for SDK consumption)
+//! - `GET  /patterns`           — List community patterns by vote score (filterable by category/verified)
+//! - `GET  /patterns/bundle`    — Signed bundle of verified patterns (for SDK consumption)
+//! - `GET  /patterns/bundle/delta` — Bundle changes since `?since={revision}`
 //! - `GET  /patterns/:id`       — Get a single pattern
+//! - `GET  /patterns/:id/stats` — Daily download time series for a pattern
+//! - `GET  /patterns/:id/overlaps` — Verified patterns matching the same samples
+//! - `GET  /patterns/:id/versions` — Version history of a pattern, with diffs
 //! - `POST /patterns`           — Submit a new pattern (requires Ed25519 signature)
-//! - `POST /patterns/:id/vote`  — Vote on a pattern
+//! - `PUT  /patterns/:id`       — Submit a new version (signed by the original author)
+//! - `POST /patterns/:id/vote`  — Vote on a pattern (or change / retract the vote)
+//!
+//! ## Scan Endpoint
+//!
+//! - `POST /scan`               — Scan text with the verified pattern set (matches + redacted text)
 //!
 //! ## Security Policy Endpoints
 //!
-//! - `GET  /policies`           — List community tool-risk policies
+//! - `GET  /policies`           — List community tool-risk policies by vote score
+//! - `GET  /policies/bundle`    — Signed bundle with one resolved policy per tool
 //! - `GET  /policies/:id`       — Get a single policy
 //! - `POST /policies`           — Submit a new policy (requires Ed25519 signature)
-//! - `POST /policies/:id/vote`  — Vote on a policy
+//! - `POST /policies/:id/vote`  — Vote on a policy (or change / retract the vote)
+//!
+//! ## Moderation Endpoints (`moderate` scope + signature of a DID in `MAINTAINER_DIDS`)
+//!
+//! - `GET  /moderation/queue`          — Patterns and policies awaiting review
+//! - `POST /patterns/:id/approve`      — Verify a pattern (it enters the bundle)
+//! - `POST /patterns/:id/reject`       — Reject a pattern with a reason
+//! - `POST /patterns/:id/deactivate`   — Deactivate a pattern with a reason
+//! - `POST /policies/:id/approve`      — Verify a policy
+//! - `POST /policies/:id/reject`       — Reject a policy with a reason
+//! - `POST /policies/:id/deactivate`   — Deactivate a policy with a reason
+//!
+//! ## Audit Log Endpoints
+//!
+//! - `GET  /audit/head`         — Current head of the hash-chained audit log
+//! - `GET  /audit/entries`      — Audit entries after `?since={seq}`
+//!
+//! ## Admin Endpoints (`admin` scope)
+//!
+//! - `GET  /admin/keys`            — List issued API keys
+//! - `POST /admin/keys`            — Issue a scoped API key (plaintext returned once)
+//! - `POST /admin/keys/:id/revoke` — Revoke an API key
+//! - `POST /admin/votes/reconcile` — Recompute vote counters now and report drift
+//!
+//! ## Rate limits
+//!
+//! Registration (nonce and `POST /register`), pattern and policy submissions
+//! and votes are limited per client IP and per signing DID; exhausted limits
+//! answer `429` with `Retry-After` (see [`rate_limit`]). Behind a proxy, set
+//! `TRUST_PROXY_HEADERS` so the client IP is read from its headers instead of
+//! the TCP peer.
+//!
+//! ## Offline verification
+//!
+//! `sigil-registry verify-audit <file>` replays a saved `GET /audit/entries`
+//! response (checking head MACs when `AUDIT_HMAC_KEY` is set) and exits
+//! non-zero at the first broken link.
+//!
+//! `sigil-registry verify-bundle <body> <keys.json> <signature> <key-id> <revision> <generated-at>`
+//! checks a saved bundle body against its `X-Sigil-Signature` / `X-Sigil-Key-Id` /
+//! `X-Sigil-Revision` / `X-Sigil-Generated-At` headers and a pinned copy of
+//! `GET /.well-known/sigil-registry-keys`.
 
+mod api_keys;
+mod audit;
 mod auth;
+mod bundle;
+mod bundle_signing;
 mod db;
+mod did;
+mod did_document;
+mod downloads;
 mod error;
 mod handlers;
+mod handlers_admin;
+mod handlers_audit;
+mod handlers_moderation;
+mod handlers_namespaces;
 mod handlers_patterns;
 mod handlers_policies;
+mod handlers_scan;
+mod jcs;
 mod models;
+mod pattern_overlap;
+mod pattern_screening;
+mod rate_limit;
+mod replay;
+mod reputation;
+mod scanner;
+mod votes;
 
-use axum::{routing::{get, post}, Router};
-use std::sync::Arc;
+use axum::{extract::DefaultBodyLimit, middleware, routing::{get, post}, Router};
+use std::{net::SocketAddr, sync::Arc};
 use tower_http::cors::CorsLayer;
 use tower_http::trace::TraceLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -44,6 +131,22 @@ pub use db::AppState;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    if let Some("verify-audit") = std::env::args().nth(1).as_deref() {
+        let path = std::env::args()
+            .nth(2)
+            .ok_or_else(|| anyhow::anyhow!("usage: sigil-registry verify-audit <entries.json>"))?;
+        return verify_audit_export(&path);
+    }
+    if let Some("verify-bundle") = std::env::args().nth(1).as_deref() {
+        let args: Vec<String> = std::env::args().skip(2).collect();
+        let [body, keys, signature, key_id, revision, generated_at] = args.as_slice() else {
+            anyhow::bail!(
+                "usage: sigil-registry verify-bundle <body> <keys.json> <signature> <key-id> <revision> <generated-at>"
+            );
+        };
+        return verify_bundle_file(body, keys, signature, key_id, revision.parse()?, generated_at.parse()?);
+    }
+
     // Initialise structured logging
     tracing_subscriber::registry()
         .with(tracing_subscriber::EnvFilter::new(
@@ -65,28 +168,79 @@ async fn main() -> anyhow::Result<()> {
     sqlx::migrate!("./migrations").run(&state.pool).await?;
     tracing::info!("Migrations applied");
 
+    downloads::spawn_aggregator(state.pool.clone());
+    votes::spawn_reconciler(state.pool.clone(), state.audit_key.clone());
+    state.rate_limiter.spawn_sweeper();
+
     let app = Router::new()
         // ── Health
         .route("/health", get(handlers::health))
+        .route("/.well-known/sigil-registry-keys", get(handlers::registry_keys))
 
         // ── DID resolution
         .route("/resolve/:did", get(handlers::resolve_did))
+        .route("/resolve/:did/history", get(handlers::did_history))
+        .route("/resolve/:did/reputation", get(handlers::did_reputation))
+        .route("/1.0/identifiers/:did", get(handlers::resolve_identifier))
+        .route("/register/nonce", get(handlers::registration_nonce))
         .route("/register", post(handlers::register_did))
         .route("/revoke/:did", post(handlers::revoke_did))
+        .route("/rotate/:did", post(handlers::rotate_key))
+        .route("/label/:did", post(handlers::update_label))
+
+        // ── Namespaces
+        .route("/namespaces",                 get(handlers_namespaces::list_namespaces)
+                                                  .post(handlers_namespaces::claim_namespace))
+        .route("/namespaces/:name",           get(handlers_namespaces::get_namespace))
+        .route("/namespaces/:name/dids",      get(handlers_namespaces::list_namespace_dids))
+        .route("/namespaces/:name/transfer",  post(handlers_namespaces::transfer_namespace))
+        .route("/namespaces/:name/delegates", post(handlers_namespaces::update_delegate))
 
         // ── Scanner Patterns
         .route("/patterns",             get(handlers_patterns::list_patterns)
                                             .post(handlers_patterns::create_pattern))
         .route("/patterns/bundle",      get(handlers_patterns::get_bundle))
-        .route("/patterns/:id",         get(handlers_patterns::get_pattern))
+        .route("/patterns/bundle/delta", get(handlers_patterns::get_bundle_delta))
+        .route("/patterns/:id",         get(handlers_patterns::get_pattern)
+                                            .put(handlers_patterns::update_pattern))
+        .route("/patterns/:id/stats",   get(handlers_patterns::pattern_stats))
+        .route("/patterns/:id/overlaps", get(handlers_patterns::pattern_overlaps))
+        .route("/patterns/:id/versions", get(handlers_patterns::pattern_versions))
         .route("/patterns/:id/vote",    post(handlers_patterns::vote_pattern))
 
+        // ── Scan
+        .route("/scan",                 post(handlers_scan::scan)
+                                            .layer(DefaultBodyLimit::max(scanner::MAX_BODY_BYTES)))
+
         // ── Security Policies
         .route("/policies",             get(handlers_policies::list_policies)
                                             .post(handlers_policies::create_policy))
+        .route("/policies/bundle",      get(handlers_policies::get_policy_bundle))
         .route("/policies/:id",         get(handlers_policies::get_policy))
         .route("/policies/:id/vote",    post(handlers_policies::vote_policy))
 
+        // ── Moderation
+        .route("/moderation/queue",         get(handlers_moderation::moderation_queue))
+        .route("/patterns/:id/approve",     post(handlers_moderation::approve_pattern))
+        .route("/patterns/:id/reject",      post(handlers_moderation::reject_pattern))
+        .route("/patterns/:id/deactivate",  post(handlers_moderation::deactivate_pattern))
+        .route("/policies/:id/approve",     post(handlers_moderation::approve_policy))
+        .route("/policies/:id/reject",      post(handlers_moderation::reject_policy))
+        .route("/policies/:id/deactivate",  post(handlers_moderation::deactivate_policy))
+
+        // ── Audit Log
+        .route("/audit/head",           get(handlers_audit::audit_head))
+        .route("/audit/entries",        get(handlers_audit::audit_entries))
+
+        // ── Admin
+        .route("/admin/keys",             get(handlers_admin::list_keys)
+                                              .post(handlers_admin::issue_key))
+        .route("/admin/keys/:id/revoke",  post(handlers_admin::revoke_key))
+        .route("/admin/votes/reconcile",  post(handlers_admin::reconcile_votes))
+
+        // Per-IP limits on registration, submissions and votes
+        .route_layer(middleware::from_fn_with_state(state.clone(), rate_limit::limit_by_ip))
+
         .layer(CorsLayer::permissive())
         .layer(TraceLayer::new_for_http())
         .with_state(state);
@@ -95,6 +249,57 @@ async fn main() -> anyhow::Result<()> {
     let listener = tokio::net::TcpListener::bind(&addr).await?;
     tracing::info!("SIGIL Registry listening on http://{addr}");
 
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
+    Ok(())
+}
+
+/// Replay a saved `GET /audit/entries` response without touching the database.
+fn verify_audit_export(path: &str) -> anyhow::Result<()> {
+    #[derive(serde::Deserialize)]
+    struct Export {
+        prev_hash: String,
+        entries: Vec<audit::AuditEntry>,
+    }
+
+    let export: Export = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    let key = std::env::var("AUDIT_HMAC_KEY").ok().map(String::into_bytes);
+
+    audit::verify_chain(&export.entries, &export.prev_hash, key.as_deref())?;
+
+    match export.entries.last() {
+        Some(last) => println!("OK: {} entries verified, head seq {} = {}", export.entries.len(), last.seq, last.entry_hash),
+        None => println!("OK: no entries"),
+    }
+    Ok(())
+}
+
+/// Verify a saved bundle body against its detached signature headers.
+fn verify_bundle_file(
+    body_path: &str,
+    keys_path: &str,
+    signature: &str,
+    key_id: &str,
+    revision: i64,
+    generated_at: i64,
+) -> anyhow::Result<()> {
+    #[derive(serde::Deserialize)]
+    struct Keys {
+        keys: Vec<bundle_signing::PublishedKey>,
+    }
+
+    let body = std::fs::read(body_path)?;
+    let keys: Keys = serde_json::from_str(&std::fs::read_to_string(keys_path)?)?;
+
+    // The digest header is redundant offline: recompute it from the body
+    let sig = bundle_signing::BundleSignature {
+        signature: signature.to_string(),
+        key_id: key_id.to_string(),
+        payload_sha256: hex::encode(<sha2::Sha256 as sha2::Digest>::digest(&body)),
+        revision,
+        generated_at,
+    };
+    bundle_signing::verify(&body, &sig, &keys.keys).map_err(|e| anyhow::anyhow!(e))?;
+
+    println!("OK: bundle revision {revision} signed by key {key_id} ({} bytes)", body.len());
     Ok(())
 }
@@ -42,6 +42,89 @@ pub struct RegisterRequest {
     pub namespace: String,
     /// Optional human-readable label
     pub label: Option<String>,
+    /// Single-use nonce from `GET /register/nonce`
+    pub nonce: String,
+    /// Proof of possession: Ed25519 signature by `public_key` over
+    /// `sigil-registry:register:{did}:{public_key}:{nonce}`, base64url-encoded
+    pub signature: String,
+    /// Controller or delegate authorising the registration — required when
+    /// the namespace is claimed
+    pub controller_did: Option<String>,
+    /// Signature by `controller_did` over
+    /// `sigil-registry:namespace-register:{namespace}:{did}:{public_key}:{nonce}`
+    pub controller_signature: Option<String>,
+}
+
+/// Request body for `POST /revoke/{did}`.
+///
+/// Required unless the caller presents the operator `X-Registry-Key`.
+#[derive(Debug, Deserialize)]
+pub struct RevokeRequest {
+    /// Unix timestamp (seconds) at which the request was signed
+    pub timestamp: i64,
+    /// Ed25519 signature over `sigil-registry:revoke:{did}:{timestamp}`
+    /// by the DID's own key, base64url-encoded
+    pub signature: String,
+}
+
+/// Request body for `POST /label/{did}`.
+#[derive(Debug, Deserialize)]
+pub struct LabelRequest {
+    /// New label; `null` clears it
+    pub label: Option<String>,
+    /// Unix timestamp (seconds) — omitted when using the operator key
+    pub timestamp: Option<i64>,
+    /// Signature by the DID's key, base64url-encoded — omitted when using the operator key
+    pub signature: Option<String>,
+    /// Single-use nonce, 16–64 characters of `[A-Za-z0-9_-]` — required with `signature`
+    pub nonce: Option<String>,
+}
+
+/// A row of the `did_events` lifecycle audit log.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct DidEvent {
+    pub id: Uuid,
+    /// `registered` | `revoked` | `key_rotated` | `label_changed`
+    pub event_type: String,
+    /// `ip:{addr}` or `key:{id}` of whoever performed the change
+    pub actor: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// Request body for `POST /rotate/{did}`.
+///
+/// Both signatures cover `sigil-registry:rotate:{did}:{new_public_key}:{timestamp}:{nonce}`:
+/// `signature` proves the current key authorises the change, `new_key_signature`
+/// proves the caller holds the new key.
+#[derive(Debug, Deserialize)]
+pub struct RotateKeyRequest {
+    /// Replacement Ed25519 public key, base64url-encoded
+    pub new_public_key: String,
+    /// Unix timestamp (seconds) at which the request was signed
+    pub timestamp: i64,
+    /// Single-use nonce, 16–64 characters of `[A-Za-z0-9_-]`
+    pub nonce: String,
+    /// Signature by the DID's current key, base64url-encoded
+    pub signature: String,
+    /// Signature by the new key, base64url-encoded
+    pub new_key_signature: String,
+}
+
+/// Query parameters for `GET /resolve/{did}`.
+#[derive(Debug, Deserialize)]
+pub struct ResolveQuery {
+    /// Include retired keys and their validity windows
+    pub include_history: Option<bool>,
+}
+
+/// A retired DID key, as stored in `did_key_history`.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct KeyHistoryEntry {
+    /// Ed25519 public key, base64url-encoded
+    pub public_key: String,
+    pub valid_from: DateTime<Utc>,
+    pub valid_until: DateTime<Utc>,
 }
 
 /// Response for `GET /resolve/{did}`.
@@ -56,6 +139,9 @@ pub struct ResolveResponse {
     pub updated_at: DateTime<Utc>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub revoked_at: Option<DateTime<Utc>>,
+    /// Retired keys, newest first — only present with `?include_history=true`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_keys: Option<Vec<KeyHistoryEntry>>,
 }
 
 impl From<DidDocument> for ResolveResponse {
@@ -69,10 +155,95 @@ impl From<DidDocument> for ResolveResponse {
             created_at: d.created_at,
             updated_at: d.updated_at,
             revoked_at: d.revoked_at,
+            previous_keys: None,
         }
     }
 }
 
+// ── Namespace models ──────────────────────────────────────────────────────────
+
+/// A claimed namespace and its controlling DID.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Namespace {
+    pub name: String,
+    pub controller_did: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request body for `POST /namespaces`.
+#[derive(Debug, Deserialize)]
+pub struct ClaimNamespaceRequest {
+    pub name: String,
+    /// DID that will control the namespace; must sign the claim
+    pub controller_did: String,
+    /// Unix timestamp (seconds) at which the request was signed
+    pub timestamp: i64,
+    /// Signature by `controller_did` over
+    /// `sigil-registry:namespace-claim:{name}:{controller_did}:{timestamp}`
+    pub signature: String,
+}
+
+/// Request body for `POST /namespaces/:name/transfer`.
+#[derive(Debug, Deserialize)]
+pub struct TransferNamespaceRequest {
+    pub new_controller_did: String,
+    /// Unix timestamp (seconds) — omitted when using the operator key
+    pub timestamp: Option<i64>,
+    /// Signature by the current controller — omitted when using the operator key
+    pub signature: Option<String>,
+    /// Single-use nonce, 16–64 characters of `[A-Za-z0-9_-]` — required with `signature`
+    pub nonce: Option<String>,
+}
+
+/// Request body for `POST /namespaces/:name/delegates`.
+#[derive(Debug, Deserialize)]
+pub struct DelegateRequest {
+    pub delegate_did: String,
+    /// `true` to grant, `false` to revoke
+    pub grant: bool,
+    /// Unix timestamp (seconds) at which the request was signed
+    pub timestamp: i64,
+    /// Single-use nonce, 16–64 characters of `[A-Za-z0-9_-]`
+    pub nonce: String,
+    /// Signature by the namespace controller
+    pub signature: String,
+}
+
+/// Query parameters for `GET /namespaces` and `GET /namespaces/:name/dids`.
+#[derive(Debug, Deserialize)]
+pub struct NamespaceQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+// ── Signed submission models ──────────────────────────────────────────────────
+
+/// Signature fields of pattern, policy and vote request bodies.
+///
+/// With `audience`, `issued_at` and `nonce` present (v1), `signature` covers
+/// the RFC 8785 canonical JSON of the whole body minus `signature`. Without
+/// them (v0, deprecated; first votes only), it covers the legacy colon-separated
+/// message built in [`crate::auth`]. See [`crate::replay`].
+#[derive(Debug, Deserialize)]
+pub struct SignedFields {
+    /// Ed25519 signature, base64url-encoded
+    pub signature: String,
+    /// Registry origin plus request path, e.g. `https://registry.example/patterns/{id}/vote`
+    pub audience: Option<String>,
+    /// Unix seconds at signing
+    pub issued_at: Option<i64>,
+    /// Single-use nonce, 16–64 characters of `[A-Za-z0-9_-]`
+    pub nonce: Option<String>,
+}
+
+impl SignedFields {
+    /// Whether these are v1 fields; only meaningful once the signature verified.
+    pub fn is_v1(&self) -> bool {
+        self.nonce.is_some()
+    }
+}
+
 // ── Scanner Pattern models ────────────────────────────────────────────────────
 
 /// A community-submitted regex pattern for PII / secret detection.
@@ -91,17 +262,52 @@ pub struct ScannerPattern {
     pub downloads: i64,
     pub votes_up: i32,
     pub votes_down: i32,
+    /// Reputation-weighted net votes; orders the listings (see [`crate::reputation`])
+    pub score: i32,
     pub verified: bool,
     pub active: bool,
+    /// `pending` | `approved` | `rejected`
+    pub review_status: String,
+    /// Maintainer DID behind the last approve / reject / deactivate
+    pub moderated_by: Option<String>,
+    pub moderated_at: Option<DateTime<Utc>>,
+    pub moderation_reason: Option<String>,
+    /// Checked test examples — see [`crate::pattern_screening::PatternExamples`]
+    pub examples: Option<serde_json::Value>,
+    /// Bumped by every `PUT /patterns/:id`; earlier versions are in `scanner_pattern_versions`
+    pub version: i32,
+    /// When the current version was submitted
+    pub version_created_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// One version of a scanner pattern, as listed by `GET /patterns/:id/versions`.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct PatternVersion {
+    pub version: i32,
+    pub description: Option<String>,
+    pub category: String,
+    pub pattern: String,
+    pub replacement_hint: Option<String>,
+    pub severity: String,
+    pub examples: Option<serde_json::Value>,
+    /// Review outcome — for earlier versions, as it stood when they were superseded
+    pub verified: bool,
+    pub review_status: String,
+    pub moderated_by: Option<String>,
+    pub moderated_at: Option<DateTime<Utc>>,
+    pub moderation_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// `None` for the current version
+    pub superseded_at: Option<DateTime<Utc>>,
+}
+
 /// Request body for `POST /patterns`.
 ///
 /// The `signature` field must be the Ed25519 signature of the canonical
 /// JSON body (without `signature` key), base64url-encoded, produced by
-/// the private key corresponding to `author_did`.
+/// the private key corresponding to `author_did` — see [`SignedFields`].
 #[derive(Debug, Deserialize)]
 pub struct CreatePatternRequest {
     pub name: String,
@@ -114,8 +320,37 @@ pub struct CreatePatternRequest {
     pub severity: Option<String>,
     /// Submitter's `did:sigil:` identifier
     pub author_did: String,
-    /// Ed25519 signature over the canonical payload, base64url-encoded
-    pub signature: String,
+    #[serde(flatten)]
+    pub signed: SignedFields,
+    /// Inputs the pattern must match (at least one)
+    #[serde(default)]
+    pub should_match: Vec<String>,
+    /// Inputs the pattern must not match (at least one)
+    #[serde(default)]
+    pub should_not_match: Vec<String>,
+}
+
+/// Request body for `PUT /patterns/:id` — a new version by the original author.
+///
+/// The name is fixed; every other field replaces the current version's.
+#[derive(Debug, Deserialize)]
+pub struct UpdatePatternRequest {
+    /// The version being created: the current version plus one
+    pub version: i32,
+    pub description: Option<String>,
+    /// `secret` | `pii` | `credential` | `financial`
+    pub category: String,
+    pub pattern: String,
+    pub replacement_hint: Option<String>,
+    /// `low` | `medium` | `high` | `critical`
+    pub severity: Option<String>,
+    /// Signed by the pattern's `author_did`
+    #[serde(flatten)]
+    pub signed: SignedFields,
+    #[serde(default)]
+    pub should_match: Vec<String>,
+    #[serde(default)]
+    pub should_not_match: Vec<String>,
 }
 
 /// Query parameters for `GET /patterns`.
@@ -127,6 +362,13 @@ pub struct PatternQuery {
     pub offset: Option<i64>,
 }
 
+/// Query parameters for `GET /patterns/:id/stats`.
+#[derive(Debug, Deserialize)]
+pub struct StatsQuery {
+    /// How many days back to report (default 30, max 365)
+    pub days: Option<i64>,
+}
+
 // ── Security Policy models ────────────────────────────────────────────────────
 
 /// A community-submitted risk classification for an MCP tool.
@@ -143,8 +385,16 @@ pub struct SecurityPolicy {
     pub author_did: Option<String>,
     pub votes_up: i32,
     pub votes_down: i32,
+    /// Reputation-weighted net votes; orders the listings (see [`crate::reputation`])
+    pub score: i32,
     pub verified: bool,
     pub active: bool,
+    /// `pending` | `approved` | `rejected`
+    pub review_status: String,
+    /// Maintainer DID behind the last approve / reject / deactivate
+    pub moderated_by: Option<String>,
+    pub moderated_at: Option<DateTime<Utc>>,
+    pub moderation_reason: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -161,8 +411,8 @@ pub struct CreatePolicyRequest {
     pub rationale: Option<String>,
     /// Submitter's `did:sigil:` identifier
     pub author_did: String,
-    /// Ed25519 signature over the canonical payload, base64url-encoded
-    pub signature: String,
+    #[serde(flatten)]
+    pub signed: SignedFields,
 }
 
 /// Query parameters for `GET /policies`.
@@ -182,17 +432,85 @@ pub struct PolicyQuery {
 pub struct VoteRequest {
     /// The voter's `did:sigil:` identifier
     pub voter_did: String,
-    /// `up` | `down`
+    /// `up` | `down` | `retract`
     pub vote: String,
-    /// Ed25519 signature over `"{target_type}:{target_id}:{vote}"`, base64url
+    #[serde(flatten)]
+    pub signed: SignedFields,
+}
+
+// ── Moderation models ─────────────────────────────────────────────────────────
+
+/// Request body for the `approve`, `reject` and `deactivate` moderation endpoints.
+///
+/// Requires an `X-Registry-Key` with the `moderate` scope; the signature
+/// attributes the decision to an individual maintainer.
+#[derive(Debug, Deserialize)]
+pub struct ModerationRequest {
+    /// The maintainer's `did:sigil:` identifier
+    pub moderator_did: String,
+    /// Why — required for `reject` and `deactivate`
+    pub reason: Option<String>,
+    /// Unix timestamp (seconds) at which the request was signed
+    pub timestamp: i64,
+    /// Single-use nonce, 16–64 characters of `[A-Za-z0-9_-]`
+    pub nonce: String,
+    /// Signature by `moderator_did` over
+    /// `sigil-registry:moderate:{target_type}:{target_id}:{action}:{reason}:{timestamp}:{nonce}`
     pub signature: String,
 }
 
+/// Query parameters for `GET /moderation/queue`.
+#[derive(Debug, Deserialize)]
+pub struct ModerationQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+// ── Audit models ──────────────────────────────────────────────────────────────
+
+/// Query parameters for `GET /audit/entries`.
+#[derive(Debug, Deserialize)]
+pub struct AuditQuery {
+    /// Return entries with `seq` greater than this (default 0 — from the start)
+    pub since: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+// ── API key models ────────────────────────────────────────────────────────────
+
+/// Request body for `POST /admin/keys`.
+#[derive(Debug, Deserialize)]
+pub struct IssueKeyRequest {
+    /// Human-readable name, e.g. `"ci-gateway-eu"`
+    pub name: String,
+    /// Subset of `register` | `revoke` | `moderate` | `read-private` | `admin`
+    pub scopes: Vec<String>,
+    /// Namespaces the key may act in (omit for unrestricted)
+    pub namespaces: Option<Vec<String>>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+// ── Scan models ───────────────────────────────────────────────────────────────
+
+/// Request body for `POST /scan`.
+#[derive(Debug, Deserialize)]
+pub struct ScanRequest {
+    /// UTF-8 text to scan, at most [`crate::scanner::MAX_TEXT_BYTES`] bytes
+    pub text: String,
+}
+
 // ── Bundle models ─────────────────────────────────────────────────────────────
 
+/// Query parameters for `GET /patterns/bundle/delta`.
+#[derive(Debug, Deserialize)]
+pub struct BundleDeltaQuery {
+    /// The bundle `revision` the client currently holds
+    pub since: i64,
+}
+
 /// An entry in the compiled pattern bundle (`GET /patterns/bundle`).
 /// This is what the `sigil-protocol` Rust crate consumes at startup.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, sqlx::FromRow)]
 pub struct BundleEntry {
     pub name: String,
     pub category: String,
@@ -200,3 +518,15 @@ pub struct BundleEntry {
     pub severity: String,
     pub replacement_hint: Option<String>,
 }
+
+/// An entry in the resolved policy bundle (`GET /policies/bundle`).
+#[derive(Debug, Serialize)]
+pub struct PolicyBundleEntry {
+    /// The policy that won resolution for this tool
+    pub id: Uuid,
+    pub tool_name: String,
+    pub risk_level: String,
+    pub requires_trust: String,
+    pub requires_confirmation: bool,
+    pub rationale: Option<String>,
+}
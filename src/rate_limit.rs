@@ -0,0 +1,370 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Token-bucket rate limits for the write endpoints.
+//!
+//! Registration, submissions and votes each verify a signature and make
+//! several database round trips, and every registration nonce is a database
+//! write, so every [`Route`] has two limits:
+//!
+//! - **per client IP** — checked by the [`limit_by_ip`] middleware before the
+//!   handler runs, so floods are turned away before any work is done;
+//! - **per DID** (author or voter) — checked by the handler with [`check_did`]
+//!   *after* the signature verified. The DID in a body is only a claim until
+//!   then; charging it earlier would let anyone drain another DID's bucket.
+//!   `POST /register` has no DID limit — its DID does not exist yet.
+//!
+//! Buckets live in Redis when `REDIS_URL` is set, so all instances share them,
+//! and in process memory otherwise (or while Redis is unreachable). In-process
+//! buckets that have refilled are dropped every [`SWEEP_INTERVAL`].
+//!
+//! The client IP is the TCP peer address unless `TRUST_PROXY_HEADERS` is on
+//! (see [`handlers::client_ip`]); trusting the headers without a proxy that
+//! overwrites them lets every request claim a fresh IP.
+//!
+//! Each limit is configured as `RATE_LIMIT_{ROUTE}_{IP|DID}=<burst>/<seconds>`:
+//! a bucket holds up to `burst` requests and refills `burst` every `seconds`.
+//! `0` disables a limit. Exhausted buckets answer `429 Too Many Requests` with
+//! `Retry-After`.
+
+use crate::{db::AppState, error::RegistryError, handlers};
+use axum::{
+    extract::{ConnectInfo, MatchedPath, Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// How often refilled in-process buckets are dropped.
+pub const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A write endpoint with its own limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Route {
+    /// `GET /register/nonce`, `POST /register` — a registration costs two tokens
+    Register,
+    /// `POST /patterns`, `PUT /patterns/:id`
+    Pattern,
+    /// `POST /policies`
+    Policy,
+    /// `POST /patterns/:id/vote`, `POST /policies/:id/vote`
+    Vote,
+}
+
+impl Route {
+    /// The limited route for a request, by method and matched route path.
+    pub fn classify(method: &Method, path: &str) -> Option<Self> {
+        match (method.as_str(), path) {
+            ("GET", "/register/nonce") | ("POST", "/register") => Some(Route::Register),
+            ("POST", "/patterns") | ("PUT", "/patterns/:id") => Some(Route::Pattern),
+            ("POST", "/policies") => Some(Route::Policy),
+            ("POST", "/patterns/:id/vote") | ("POST", "/policies/:id/vote") => Some(Route::Vote),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Route::Register => "register",
+            Route::Pattern => "pattern",
+            Route::Policy => "policy",
+            Route::Vote => "vote",
+        }
+    }
+}
+
+/// One token bucket: up to `burst` requests, refilled at `burst` per `period`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limit {
+    pub burst: u32,
+    pub period: Duration,
+}
+
+impl Limit {
+    const fn per_hour(burst: u32) -> Option<Self> {
+        Some(Self { burst, period: Duration::from_secs(3600) })
+    }
+
+    /// Parse `<burst>/<seconds>`; `0` means unlimited (`Ok(None)`).
+    pub fn parse(spec: &str) -> Result<Option<Self>, String> {
+        if spec.trim() == "0" {
+            return Ok(None);
+        }
+        let (burst, secs) = spec
+            .split_once('/')
+            .ok_or_else(|| format!("rate limit '{spec}' is not <burst>/<seconds>"))?;
+        let burst: u32 = burst.trim().parse().map_err(|e| format!("rate limit '{spec}': {e}"))?;
+        let secs: u64 = secs.trim().parse().map_err(|e| format!("rate limit '{spec}': {e}"))?;
+        if burst == 0 || secs == 0 {
+            return Ok(None);
+        }
+        Ok(Some(Self { burst, period: Duration::from_secs(secs) }))
+    }
+}
+
+/// Per-IP and per-DID limits of every [`Route`]; `None` is unlimited.
+#[derive(Debug, Clone)]
+pub struct RateLimits {
+    limits: HashMap<(Route, Scope), Option<Limit>>,
+}
+
+/// What a bucket is keyed by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Scope {
+    Ip,
+    Did,
+}
+
+impl Scope {
+    fn as_str(self) -> &'static str {
+        match self {
+            Scope::Ip => "ip",
+            Scope::Did => "did",
+        }
+    }
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        let limits = HashMap::from([
+            ((Route::Register, Scope::Ip), Limit::per_hour(20)),
+            ((Route::Register, Scope::Did), None),
+            ((Route::Pattern, Scope::Ip), Limit::per_hour(60)),
+            ((Route::Pattern, Scope::Did), Limit::per_hour(20)),
+            ((Route::Policy, Scope::Ip), Limit::per_hour(60)),
+            ((Route::Policy, Scope::Did), Limit::per_hour(20)),
+            ((Route::Vote, Scope::Ip), Limit::per_hour(600)),
+            ((Route::Vote, Scope::Did), Limit::per_hour(120)),
+        ]);
+        Self { limits }
+    }
+}
+
+impl RateLimits {
+    /// Defaults, overridden by `RATE_LIMIT_{REGISTER|PATTERN|POLICY|VOTE}_{IP|DID}`.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let mut limits = Self::default();
+        for (&(route, scope), limit) in limits.limits.iter_mut() {
+            let name = format!("RATE_LIMIT_{}_{}", route.as_str(), scope.as_str()).to_uppercase();
+            if let Ok(spec) = std::env::var(&name) {
+                *limit = Limit::parse(&spec).map_err(|e| anyhow::anyhow!("{name}: {e}"))?;
+            }
+        }
+        Ok(limits)
+    }
+
+    fn get(&self, route: Route, scope: Scope) -> Option<Limit> {
+        self.limits.get(&(route, scope)).copied().flatten()
+    }
+}
+
+// ── Buckets ───────────────────────────────────────────────────────────────────
+
+/// Tokens per second.
+fn rate(limit: Limit) -> f64 {
+    limit.burst as f64 / limit.period.as_secs_f64()
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn full(limit: Limit, now: Instant) -> Self {
+        Self { tokens: limit.burst as f64, updated: now }
+    }
+
+    /// Tokens available at `now`.
+    fn refilled(&self, limit: Limit, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        (self.tokens + elapsed * rate(limit)).min(limit.burst as f64)
+    }
+
+    /// Take one token or return how long until one is available.
+    fn take(&mut self, limit: Limit, now: Instant) -> Result<(), Duration> {
+        self.tokens = self.refilled(limit, now);
+        self.updated = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / rate(limit)))
+        }
+    }
+
+    fn is_full(&self, limit: Limit, now: Instant) -> bool {
+        self.refilled(limit, now) >= limit.burst as f64
+    }
+}
+
+/// Configured limits plus the in-process buckets used without Redis.
+#[derive(Clone)]
+pub struct RateLimiter {
+    limits: Arc<RateLimits>,
+    local: Arc<Mutex<HashMap<String, (Limit, Bucket)>>>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> Self {
+        Self { limits: Arc::new(limits), local: Arc::default() }
+    }
+
+    fn take_local(&self, key: String, limit: Limit) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut buckets = self.local.lock().unwrap_or_else(|e| e.into_inner());
+        let (_, bucket) = buckets.entry(key).or_insert_with(|| (limit, Bucket::full(limit, now)));
+        bucket.take(limit, now)
+    }
+
+    /// Drop in-process buckets that have refilled — a full bucket is the same
+    /// as no bucket. Returns how many are left.
+    fn sweep(&self, now: Instant) -> usize {
+        let mut buckets = self.local.lock().unwrap_or_else(|e| e.into_inner());
+        buckets.retain(|_, (limit, bucket)| !bucket.is_full(*limit, now));
+        buckets.len()
+    }
+
+    /// Run [`Self::sweep`] every [`SWEEP_INTERVAL`] for the life of the process.
+    pub fn spawn_sweeper(&self) {
+        let limiter = self.clone();
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(SWEEP_INTERVAL);
+            loop {
+                tick.tick().await;
+                let left = limiter.sweep(Instant::now());
+                tracing::debug!("{left} in-process rate limit buckets after sweep");
+            }
+        });
+    }
+}
+
+/// Atomically refill and take from a bucket stored as a Redis hash.
+/// Returns `{allowed, retry_after_ms}`.
+const REDIS_TAKE: &str = r"
+local burst, period_ms = tonumber(ARGV[1]), tonumber(ARGV[2])
+local t = redis.call('TIME')
+local now = t[1] * 1000 + math.floor(t[2] / 1000)
+local b = redis.call('HMGET', KEYS[1], 'tokens', 'ts')
+local tokens, ts = tonumber(b[1]) or burst, tonumber(b[2]) or now
+tokens = math.min(burst, tokens + math.max(0, now - ts) * burst / period_ms)
+local allowed, wait = 0, 0
+if tokens >= 1 then
+  tokens, allowed = tokens - 1, 1
+else
+  wait = math.ceil((1 - tokens) * period_ms / burst)
+end
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'ts', now)
+redis.call('PEXPIRE', KEYS[1], period_ms)
+return {allowed, wait}
+";
+
+/// Take one token from the `(route, scope, key)` bucket.
+async fn take(state: &AppState, route: Route, scope: Scope, key: &str) -> Result<(), RegistryError> {
+    let Some(limit) = state.rate_limiter.limits.get(route, scope) else {
+        return Ok(());
+    };
+    let bucket_key = format!("ratelimit:{}:{}:{key}", route.as_str(), scope.as_str());
+
+    let mut verdict = None;
+    if let Some(mut cache) = state.cache.clone() {
+        let reply: redis::RedisResult<(i64, u64)> = redis::Script::new(REDIS_TAKE)
+            .key(&bucket_key)
+            .arg(limit.burst)
+            .arg(limit.period.as_millis() as u64)
+            .invoke_async(&mut cache)
+            .await;
+        match reply {
+            Ok((1, _)) => verdict = Some(Ok(())),
+            Ok((_, wait_ms)) => verdict = Some(Err(Duration::from_millis(wait_ms))),
+            Err(e) => tracing::warn!("Redis rate limit failed (falling back to in-process buckets): {e}"),
+        }
+    }
+    let verdict = verdict.unwrap_or_else(|| state.rate_limiter.take_local(bucket_key, limit));
+
+    verdict.map_err(|wait| {
+        tracing::info!("Rate limited {} request by {} {key}", route.as_str(), scope.as_str());
+        RegistryError::RateLimited { retry_after_secs: wait.as_secs_f64().ceil().max(1.0) as u64 }
+    })
+}
+
+/// Charge `did` for a request to `route`. Call only once its signature verified.
+pub async fn check_did(state: &AppState, route: Route, did: &str) -> Result<(), RegistryError> {
+    take(state, route, Scope::Did, did).await
+}
+
+/// Middleware charging the client IP of every request to a limited [`Route`].
+pub async fn limit_by_ip(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    matched: Option<MatchedPath>,
+    request: Request,
+    next: Next,
+) -> Result<Response, RegistryError> {
+    let route = matched.and_then(|m| Route::classify(request.method(), m.as_str()));
+    if let Some(route) = route {
+        let ip = handlers::client_ip(request.headers(), &peer, state.trust_proxy_headers);
+        take(&state, route, Scope::Ip, &ip).await?;
+    }
+    Ok(next.run(request).await)
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_limits() {
+        assert_eq!(Limit::parse("20/3600"), Ok(Some(Limit { burst: 20, period: Duration::from_secs(3600) })));
+        assert_eq!(Limit::parse("0"), Ok(None));
+        assert_eq!(Limit::parse("5/0"), Ok(None));
+        assert!(Limit::parse("20").is_err());
+        assert!(Limit::parse("many/60").is_err());
+    }
+
+    #[test]
+    fn bucket_refills_at_rate() {
+        let limit = Limit { burst: 2, period: Duration::from_secs(60) };
+        let start = Instant::now();
+        let mut bucket = Bucket::full(limit, start);
+
+        assert!(bucket.take(limit, start).is_ok());
+        assert!(bucket.take(limit, start).is_ok());
+        let wait = bucket.take(limit, start).unwrap_err();
+        assert_eq!(wait.as_secs(), 30);
+
+        assert!(bucket.take(limit, start + Duration::from_secs(30)).is_ok());
+        assert!(bucket.take(limit, start + Duration::from_secs(31)).is_err());
+        assert!(bucket.is_full(limit, start + Duration::from_secs(120)));
+    }
+
+    #[test]
+    fn sweep_drops_refilled_buckets() {
+        let limiter = RateLimiter::new(RateLimits::default());
+        let limit = Limit { burst: 1, period: Duration::from_secs(60) };
+        limiter.take_local("a".into(), limit).unwrap();
+        limiter.take_local("b".into(), limit).unwrap();
+        assert!(limiter.take_local("a".into(), limit).is_err());
+
+        assert_eq!(limiter.sweep(Instant::now()), 2);
+        assert_eq!(limiter.sweep(Instant::now() + Duration::from_secs(60)), 0);
+    }
+
+    #[test]
+    fn classifies_write_routes() {
+        assert_eq!(Route::classify(&Method::PUT, "/patterns/:id"), Some(Route::Pattern));
+        assert_eq!(Route::classify(&Method::POST, "/policies/:id/vote"), Some(Route::Vote));
+        assert_eq!(Route::classify(&Method::GET, "/register/nonce"), Some(Route::Register));
+        assert_eq!(Route::classify(&Method::GET, "/patterns"), None);
+    }
+}
@@ -0,0 +1,262 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Replay protection and full-payload signatures for submissions and votes.
+//!
+//! The original (v0) canonical messages for patterns, policies and votes sign
+//! only a few fields and carry no freshness or audience: `severity`,
+//! `description` or a policy's `requires_confirmation` can be altered in
+//! transit, and a captured signature stays valid forever and on every
+//! registry instance.
+//!
+//! A v1 request body carries three more members and is signed whole:
+//!
+//! - `audience` — this registry's origin plus the request path, e.g.
+//!   `https://registry.example/patterns/{id}/vote`,
+//! - `issued_at` — Unix seconds, which must lie within the clock-skew window,
+//! - `nonce` — accepted once per signer.
+//!
+//! The signature covers the RFC 8785 canonical JSON ([`crate::jcs`]) of the
+//! body as received, minus `signature`, so fields unknown to this version of
+//! the registry are covered too. `test-vectors/signatures.json` has worked
+//! examples.
+//!
+//! Used nonces are recorded in `signature_nonces` for twice the window — after
+//! that `issued_at` alone rejects the request. PostgreSQL is the record: Redis,
+//! when available, only turns away repeats before the database round trip, so
+//! a Redis flush or outage cannot make a used nonce usable again.
+//!
+//! Bodies without `audience`, `issued_at` and `nonce` are verified as v0 while
+//! `ACCEPT_V0_SIGNATURES` is on (the default during the deprecation period).
+//! A v0 vote can only be a first `up` or `down`: changing or retracting a vote
+//! needs v1, since a replayable v0 vote could undo it.
+
+use crate::{
+    auth,
+    db::{env_var, AppState},
+    error::RegistryError,
+    jcs,
+    models::SignedFields,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// Accepted nonce lengths; nonces are base64url / hex / UUID strings.
+const NONCE_LEN: std::ops::RangeInclusive<usize> = 16..=64;
+
+/// How signed submissions are checked for freshness and audience.
+#[derive(Debug, Clone)]
+pub struct ReplayPolicy {
+    /// This registry's origin, e.g. `https://registry.sigil-protocol.org`
+    pub origin: String,
+    /// Allowed clock skew for `issued_at` and request timestamps, in seconds
+    pub max_skew_secs: i64,
+    /// Whether v0 bodies without `audience`, `issued_at` and `nonce` are still accepted
+    pub accept_v0: bool,
+}
+
+impl Default for ReplayPolicy {
+    fn default() -> Self {
+        Self {
+            origin: "http://localhost:3100".into(),
+            max_skew_secs: auth::TIMESTAMP_WINDOW_SECS,
+            accept_v0: true,
+        }
+    }
+}
+
+impl ReplayPolicy {
+    /// Defaults, overridden by `REGISTRY_ORIGIN`, `SIGNATURE_MAX_SKEW_SECS`
+    /// (positive seconds) and `ACCEPT_V0_SIGNATURES` (`true` / `false`).
+    /// Invalid values fail startup.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let d = Self::default();
+        let policy = Self {
+            origin: env_var::<String>("REGISTRY_ORIGIN")?
+                .map(|o| o.trim_end_matches('/').to_string())
+                .unwrap_or(d.origin),
+            max_skew_secs: env_var("SIGNATURE_MAX_SKEW_SECS")?.unwrap_or(d.max_skew_secs),
+            accept_v0: env_var("ACCEPT_V0_SIGNATURES")?.unwrap_or(d.accept_v0),
+        };
+        if policy.max_skew_secs <= 0 {
+            anyhow::bail!("SIGNATURE_MAX_SKEW_SECS must be positive, not {}", policy.max_skew_secs);
+        }
+        Ok(policy)
+    }
+}
+
+/// Deserialize a signed request body, keeping the raw JSON for [`verify_signed`].
+pub fn parse_body<T: DeserializeOwned>(body: &Value) -> Result<T, RegistryError> {
+    T::deserialize(body).map_err(|e| RegistryError::Validation(format!("invalid request body: {e}")))
+}
+
+/// The bytes a v1 signature covers: the canonical JSON of `body` without `signature`.
+pub fn signing_input(body: &Value) -> String {
+    let mut unsigned = body.clone();
+    if let Some(members) = unsigned.as_object_mut() {
+        members.remove("signature");
+    }
+    jcs::canonicalize(&unsigned)
+}
+
+/// Verify the signature of a request `body` sent to `path`, consuming its
+/// nonce if it is v1.
+///
+/// `v0_message` is the legacy message, checked only for bodies without
+/// `audience`, `issued_at` and `nonce` (subject to [`ReplayPolicy::accept_v0`]);
+/// `None` requires v1.
+pub async fn verify_signed(
+    state: &AppState,
+    public_key: &str,
+    signer_did: &str,
+    signed: &SignedFields,
+    body: &Value,
+    path: &str,
+    v0_message: Option<&str>,
+) -> Result<(), RegistryError> {
+    let policy = &state.replay;
+
+    let (audience, issued_at, nonce) = match (&signed.audience, signed.issued_at, &signed.nonce) {
+        (Some(audience), Some(issued_at), Some(nonce)) => (audience, issued_at, nonce),
+        (None, None, None) => {
+            let Some(v0_message) = v0_message.filter(|_| policy.accept_v0) else {
+                return Err(RegistryError::InvalidSignature(
+                    "unversioned signatures are not accepted here; sign the canonical body with audience, issued_at and nonce"
+                        .into(),
+                ));
+            };
+            auth::verify_signature(public_key, v0_message, &signed.signature)
+                .map_err(RegistryError::InvalidSignature)?;
+            tracing::warn!("Deprecated v0 signature accepted from {signer_did}");
+            return Ok(());
+        }
+        _ => {
+            return Err(RegistryError::Validation(
+                "audience, issued_at and nonce must be given together".into(),
+            ))
+        }
+    };
+
+    let expected = format!("{}{path}", policy.origin);
+    if *audience != expected {
+        return Err(RegistryError::InvalidSignature(format!("audience must be {expected}")));
+    }
+
+    check_nonce(nonce)?;
+
+    auth::check_timestamp(issued_at, chrono::Utc::now().timestamp(), policy.max_skew_secs)
+        .map_err(RegistryError::InvalidSignature)?;
+
+    auth::verify_signature(public_key, &signing_input(body), &signed.signature)
+        .map_err(RegistryError::InvalidSignature)?;
+
+    // Only consume after the signature checks out, so nobody can burn another signer's nonces
+    use_nonce(state, signer_did, nonce).await
+}
+
+/// Spend the `nonce` of a message `signer_did` signed. Call only once the
+/// signature verified.
+///
+/// For the colon-separated messages that carry a nonce next to their timestamp
+/// (see [`crate::auth`]); v1 bodies go through [`verify_signed`].
+pub async fn use_nonce(state: &AppState, signer_did: &str, nonce: &str) -> Result<(), RegistryError> {
+    check_nonce(nonce)?;
+    if !consume_nonce(state, signer_did, nonce).await? {
+        return Err(RegistryError::InvalidSignature("nonce has already been used".into()));
+    }
+    Ok(())
+}
+
+fn check_nonce(nonce: &str) -> Result<(), RegistryError> {
+    if !NONCE_LEN.contains(&nonce.len())
+        || !nonce.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(RegistryError::Validation(format!(
+            "nonce must be {} to {} characters of [A-Za-z0-9_-]",
+            NONCE_LEN.start(),
+            NONCE_LEN.end()
+        )));
+    }
+    Ok(())
+}
+
+/// Record `(signer, nonce)` as used. Returns `false` if it already was.
+async fn consume_nonce(state: &AppState, signer_did: &str, nonce: &str) -> Result<bool, RegistryError> {
+    let ttl = 2 * state.replay.max_skew_secs;
+
+    // Fast pre-check: a nonce Redis has seen is certainly used. One it has not
+    // seen may still be in PostgreSQL (after a flush, or from an outage), so
+    // the insert below decides.
+    if let Some(mut cache) = state.cache.clone() {
+        let stored: redis::RedisResult<Option<String>> = redis::cmd("SET")
+            .arg(format!("sig-nonce:{signer_did}:{nonce}"))
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(ttl)
+            .query_async(&mut cache)
+            .await;
+        match stored {
+            Ok(None) => return Ok(false),
+            Ok(Some(_)) => {}
+            Err(e) => tracing::warn!("Redis nonce SET failed (checking PostgreSQL only): {e}"),
+        }
+    }
+
+    // Opportunistic cleanup keeps the table small without a background job
+    sqlx::query("DELETE FROM signature_nonces WHERE expires_at < NOW()")
+        .execute(&state.pool)
+        .await?;
+
+    let inserted = sqlx::query(
+        "INSERT INTO signature_nonces (signer_did, nonce, expires_at)
+         VALUES ($1, $2, NOW() + make_interval(secs => $3))
+         ON CONFLICT (signer_did, nonce) DO NOTHING",
+    )
+    .bind(signer_did)
+    .bind(nonce)
+    .bind(ttl as f64)
+    .execute(&state.pool)
+    .await?;
+
+    Ok(inserted.rows_affected() == 1)
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(serde::Deserialize)]
+    struct Vector {
+        name: String,
+        public_key: String,
+        body: Value,
+        signing_input: String,
+    }
+
+    #[test]
+    fn shipped_signature_vectors() {
+        let vectors: Vec<Vector> = serde_json::from_str(include_str!("../test-vectors/signatures.json")).unwrap();
+        assert!(!vectors.is_empty());
+        for v in vectors {
+            assert_eq!(signing_input(&v.body), v.signing_input, "vector '{}'", v.name);
+            let signature = v.body["signature"].as_str().unwrap();
+            auth::verify_signature(&v.public_key, &v.signing_input, signature)
+                .unwrap_or_else(|e| panic!("vector '{}': {e}", v.name));
+        }
+    }
+
+    #[test]
+    fn every_field_is_signed() {
+        let vectors: Vec<Vector> = serde_json::from_str(include_str!("../test-vectors/signatures.json")).unwrap();
+        let policy = vectors.iter().find(|v| v.body.get("requires_confirmation").is_some()).unwrap();
+
+        let mut tampered = policy.body.clone();
+        tampered["requires_confirmation"] = Value::Bool(false);
+        let signature = policy.body["signature"].as_str().unwrap();
+        assert!(auth::verify_signature(&policy.public_key, &signing_input(&tampered), signature).is_err());
+    }
+}
@@ -0,0 +1,118 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Per-DID reputation, used to weight votes.
+//!
+//! Registering a DID is cheap, so a batch of fresh DIDs must not be able to
+//! vote an entry to the top of the listings. A DID's reputation is 0–100
+//! points:
+//!
+//! - **age** — one point per day since registration, at most [`MAX_AGE_POINTS`],
+//! - **verified contributions** — [`POINTS_PER_CONTRIBUTION`] per active,
+//!   verified pattern or policy it authored, at most [`MAX_CONTRIBUTION_POINTS`],
+//! - **accepted votes** — [`POINTS_PER_ACCEPTED_VOTE`] per vote cast *before*
+//!   a maintainer decided the entry the same way (an up-vote on an entry that
+//!   was verified, a down-vote on one that was rejected or deactivated), at
+//!   most [`MAX_ACCEPTED_VOTE_POINTS`].
+//!
+//! Voting requires at least `MIN_VOTE_REPUTATION` points (default
+//! [`DEFAULT_MIN_VOTE_REPUTATION`]), at least one verified contribution or
+//! accepted vote among them: age alone only takes waiting, so a batch of DIDs
+//! registered today would otherwise be a voting bloc in ten days.
+//!
+//! Each vote stores the voter's reputation at that moment as its weight (see
+//! [`crate::votes`]); migration 0017 repeats this formula to weight earlier
+//! votes.
+
+use serde::Serialize;
+use sqlx::PgConnection;
+
+pub const MAX_AGE_POINTS: i32 = 30;
+pub const POINTS_PER_CONTRIBUTION: i32 = 10;
+pub const MAX_CONTRIBUTION_POINTS: i32 = 50;
+pub const POINTS_PER_ACCEPTED_VOTE: i32 = 2;
+pub const MAX_ACCEPTED_VOTE_POINTS: i32 = 20;
+
+/// Points needed to vote unless `MIN_VOTE_REPUTATION` says otherwise, e.g. one
+/// verified contribution.
+pub const DEFAULT_MIN_VOTE_REPUTATION: i32 = 10;
+
+/// A DID's reputation and what it is made of.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Reputation {
+    /// Total points, 0–100
+    pub points: i32,
+    pub age_days: i64,
+    pub verified_contributions: i64,
+    pub accepted_votes: i64,
+}
+
+impl Reputation {
+    pub fn new(age_days: i64, verified_contributions: i64, accepted_votes: i64) -> Self {
+        let capped = |n: i64, per: i32, max: i32| n.saturating_mul(per as i64).clamp(0, max as i64) as i32;
+        let points = capped(age_days, 1, MAX_AGE_POINTS)
+            + capped(verified_contributions, POINTS_PER_CONTRIBUTION, MAX_CONTRIBUTION_POINTS)
+            + capped(accepted_votes, POINTS_PER_ACCEPTED_VOTE, MAX_ACCEPTED_VOTE_POINTS);
+        Self { points, age_days, verified_contributions, accepted_votes }
+    }
+
+    /// Whether these points, including some not earned by age, reach `min`.
+    ///
+    /// A `min` of 0 or less lets anyone vote (dev mode).
+    pub fn can_vote(&self, min: i32) -> bool {
+        min <= 0 || (self.points >= min && self.verified_contributions + self.accepted_votes > 0)
+    }
+}
+
+/// The current reputation of `did`, or `None` if it is not registered.
+pub async fn of(conn: &mut PgConnection, did: &str) -> Result<Option<Reputation>, sqlx::Error> {
+    let row: Option<(i64, i64, i64)> = sqlx::query_as(
+        "SELECT
+             FLOOR(EXTRACT(EPOCH FROM NOW() - d.created_at) / 86400)::BIGINT,
+             (SELECT COUNT(*) FROM scanner_patterns
+              WHERE author_did = d.did AND verified = TRUE AND active = TRUE)
+           + (SELECT COUNT(*) FROM security_policies
+              WHERE author_did = d.did AND verified = TRUE AND active = TRUE),
+             (SELECT COUNT(*) FROM registry_votes v
+              LEFT JOIN scanner_patterns  p ON v.target_type = 'pattern' AND p.id = v.target_id
+              LEFT JOIN security_policies s ON v.target_type = 'policy'  AND s.id = v.target_id
+              WHERE v.voter_did = d.did
+                AND v.voted_at < COALESCE(p.moderated_at, s.moderated_at)
+                AND ((v.vote = 'up'   AND COALESCE(p.verified, s.verified))
+                  OR (v.vote = 'down' AND (COALESCE(p.review_status, s.review_status) = 'rejected'
+                                           OR NOT COALESCE(p.active, s.active)))))
+         FROM dids d WHERE d.did = $1",
+    )
+    .bind(did)
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(row.map(|(age, contributions, accepted)| Reputation::new(age, contributions, accepted)))
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn components_are_capped() {
+        assert_eq!(Reputation::new(0, 0, 0).points, 0);
+        assert_eq!(Reputation::new(9, 0, 0).points, 9);
+        assert_eq!(Reputation::new(0, 1, 0).points, DEFAULT_MIN_VOTE_REPUTATION);
+        assert_eq!(Reputation::new(400, 2, 3).points, 30 + 20 + 6);
+        assert_eq!(Reputation::new(i64::MAX, 99, 99).points, 100);
+    }
+
+    #[test]
+    fn age_alone_cannot_vote() {
+        let min = DEFAULT_MIN_VOTE_REPUTATION;
+        assert!(!Reputation::new(365, 0, 0).can_vote(min));
+        assert!(Reputation::new(0, 1, 0).can_vote(min));
+        assert!(Reputation::new(8, 0, 1).can_vote(min));
+        assert!(!Reputation::new(7, 0, 1).can_vote(min));
+        assert!(Reputation::new(0, 0, 0).can_vote(0));
+    }
+}
@@ -0,0 +1,393 @@
+// SPDX-License-Identifier: EUPL-1.2
+// Copyright (c) 2026 Benjamin Küttner <benjamin.kuettner@icloud.com>
+// Patent Pending — DE Gebrauchsmuster, filed 2026-02-23
+
+//! Vote recording and counter reconciliation.
+//!
+//! `registry_votes` is the source of truth; `votes_up` / `votes_down` and the
+//! reputation-weighted `score` on patterns and policies are denormalised
+//! counters — `score` orders the listings and picks the winning policy of the
+//! bundle, the raw counts only break ties. [`record`] changes votes and
+//! counters in one transaction and holds a lock on the voted-on row meanwhile,
+//! so concurrent votes on the same entry are applied one after another.
+//!
+//! A vote can be cast, changed (`up` ↔ `down`) or retracted. Casting or
+//! changing one needs the minimum reputation and stores the voter's current
+//! reputation as its weight (see [`crate::reputation`]); retracting is always
+//! allowed. Counters can still drift — votes disappear with their DID via
+//! `ON DELETE CASCADE` — so [`reconcile`] recomputes them every
+//! [`RECONCILE_INTERVAL`] and reports what it corrected.
+
+use crate::{audit, db::AppState, error::RegistryError, reputation};
+use serde::Serialize;
+use serde_json::json;
+use sqlx::PgPool;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// How often the vote counters are checked against `registry_votes`.
+pub const RECONCILE_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// What can be voted on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Pattern,
+    Policy,
+}
+
+impl Target {
+    pub const ALL: [Target; 2] = [Target::Pattern, Target::Policy];
+
+    /// `target_type` in `registry_votes` and in signed vote messages.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Target::Pattern => "pattern",
+            Target::Policy => "policy",
+        }
+    }
+
+    fn table(self) -> &'static str {
+        match self {
+            Target::Pattern => "scanner_patterns",
+            Target::Policy => "security_policies",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Target::Pattern => "Pattern",
+            Target::Policy => "Policy",
+        }
+    }
+}
+
+/// The `vote` field of a vote request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ballot {
+    Up,
+    Down,
+    /// Withdraw an earlier vote
+    Retract,
+}
+
+impl Ballot {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "up" => Some(Ballot::Up),
+            "down" => Some(Ballot::Down),
+            "retract" => Some(Ballot::Retract),
+            _ => None,
+        }
+    }
+
+    /// The vote stored in `registry_votes`; `None` for a retraction.
+    fn stored(self) -> Option<&'static str> {
+        match self {
+            Ballot::Up => Some("up"),
+            Ballot::Down => Some("down"),
+            Ballot::Retract => None,
+        }
+    }
+}
+
+/// Result of [`record`].
+#[derive(Debug, Serialize)]
+pub struct Recorded {
+    /// The voter's vote before this request, if any
+    pub previous: Option<String>,
+    /// Weight of the new vote — the voter's reputation; 0 for a retraction
+    pub weight: i32,
+    pub votes_up: i32,
+    pub votes_down: i32,
+    pub score: i32,
+}
+
+/// Counter changes `(up, down, score)` for replacing vote `previous` of
+/// weight `previous_weight` with `next` of weight `weight`.
+fn counter_delta(previous: Option<&str>, previous_weight: i32, next: Option<&str>, weight: i32) -> (i32, i32, i32) {
+    let counts = |v: Option<&str>, w: i32| match v {
+        Some("up") => (1, 0, w),
+        Some("down") => (0, 1, -w),
+        _ => (0, 0, 0),
+    };
+    let (pu, pd, ps) = counts(previous, previous_weight);
+    let (nu, nd, ns) = counts(next, weight);
+    (nu - pu, nd - pd, ns - ps)
+}
+
+/// Cast, change or retract `voter_did`'s vote on an active entry, updating
+/// the counters and the audit log in the same transaction.
+///
+/// Repeating the current vote is `AlreadyVoted`; retracting without a vote is
+/// `ResourceNotFound`; casting or changing a vote without enough reputation
+/// (see [`Reputation::can_vote`](crate::reputation::Reputation::can_vote)) is `Forbidden`.
+/// `v1` says how the request was signed: a v0 signature may only cast a
+/// first vote, anything else is `InvalidSignature`.
+pub async fn record(
+    state: &AppState,
+    target: Target,
+    id: Uuid,
+    voter_did: &str,
+    ballot: Ballot,
+    v1: bool,
+) -> Result<Recorded, RegistryError> {
+    let mut tx = state.pool.begin().await?;
+
+    // Lock the entry: votes on it are serialised from here to commit
+    let exists: Option<i32> = sqlx::query_scalar(&format!(
+        "SELECT 1 FROM {} WHERE id = $1 AND active = TRUE FOR UPDATE",
+        target.table()
+    ))
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    if exists.is_none() {
+        return Err(RegistryError::ResourceNotFound(format!("{} {id} not found", target.label())));
+    }
+
+    let existing: Option<(String, i32)> = sqlx::query_as(
+        "SELECT vote, weight FROM registry_votes WHERE voter_did = $1 AND target_type = $2 AND target_id = $3",
+    )
+    .bind(voter_did)
+    .bind(target.as_str())
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let (previous, previous_weight) = existing.unzip();
+
+    let next = ballot.stored();
+
+    // A v0 signature has no nonce, so it could be replayed to undo a later change
+    if !v1 && previous.is_some() && previous.as_deref() != next {
+        return Err(RegistryError::InvalidSignature(
+            "changing or retracting a vote requires a v1 signature (audience, issued_at, nonce)".into(),
+        ));
+    }
+    let weight = match next {
+        Some(_) if previous.as_deref() != next => {
+            let reputation = reputation::of(&mut tx, voter_did)
+                .await?
+                .ok_or_else(|| RegistryError::UnknownAuthor(voter_did.to_string()))?;
+            if !reputation.can_vote(state.min_vote_reputation) {
+                return Err(RegistryError::Forbidden(format!(
+                    "voting requires a reputation of {}, including a verified contribution or accepted vote; {voter_did} has {}",
+                    state.min_vote_reputation, reputation.points
+                )));
+            }
+            reputation.points
+        }
+        _ => 0,
+    };
+
+    let action = match (previous.as_deref(), next) {
+        (p, n) if p == n && n.is_some() => return Err(RegistryError::AlreadyVoted),
+        (None, None) => {
+            return Err(RegistryError::ResourceNotFound(format!(
+                "No vote by {voter_did} on {} {id} to retract",
+                target.as_str()
+            )))
+        }
+        (None, Some(vote)) => {
+            sqlx::query(
+                "INSERT INTO registry_votes (voter_did, target_type, target_id, vote, weight)
+                 VALUES ($1, $2, $3, $4, $5)",
+            )
+            .bind(voter_did)
+            .bind(target.as_str())
+            .bind(id)
+            .bind(vote)
+            .bind(weight)
+            .execute(&mut *tx)
+            .await?;
+            "vote.cast"
+        }
+        (Some(_), Some(vote)) => {
+            sqlx::query(
+                "UPDATE registry_votes SET vote = $4, weight = $5, voted_at = NOW()
+                 WHERE voter_did = $1 AND target_type = $2 AND target_id = $3",
+            )
+            .bind(voter_did)
+            .bind(target.as_str())
+            .bind(id)
+            .bind(vote)
+            .bind(weight)
+            .execute(&mut *tx)
+            .await?;
+            "vote.changed"
+        }
+        (Some(_), None) => {
+            sqlx::query(
+                "DELETE FROM registry_votes WHERE voter_did = $1 AND target_type = $2 AND target_id = $3",
+            )
+            .bind(voter_did)
+            .bind(target.as_str())
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+            "vote.retracted"
+        }
+    };
+
+    let (up, down, score) = counter_delta(previous.as_deref(), previous_weight.unwrap_or(0), next, weight);
+    let (votes_up, votes_down, score): (i32, i32, i32) = sqlx::query_as(&format!(
+        "UPDATE {} SET votes_up = votes_up + $2, votes_down = votes_down + $3, score = score + $4,
+                       updated_at = NOW()
+         WHERE id = $1 RETURNING votes_up, votes_down, score",
+        target.table()
+    ))
+    .bind(id)
+    .bind(up)
+    .bind(down)
+    .bind(score)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    audit::append(
+        &mut tx,
+        state.audit_key.as_deref(),
+        action,
+        &id.to_string(),
+        Some(voter_did),
+        json!({ "target_type": target.as_str(), "vote": next, "weight": weight, "previous": previous }),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Recorded { previous, weight, votes_up, votes_down, score })
+}
+
+// ── Reconciliation ────────────────────────────────────────────────────────────
+
+/// Counters of one entry that disagreed with `registry_votes`, before correction.
+#[derive(Debug, Serialize)]
+pub struct Drift {
+    pub target_type: &'static str,
+    pub id: Uuid,
+    pub votes_up: i32,
+    pub votes_down: i32,
+    pub score: i32,
+    pub counted_up: i32,
+    pub counted_down: i32,
+    pub counted_score: i32,
+}
+
+/// Run [`reconcile`] every [`RECONCILE_INTERVAL`] for the life of the process.
+pub fn spawn_reconciler(pool: PgPool, audit_key: Option<Vec<u8>>) {
+    tokio::spawn(async move {
+        let mut tick = tokio::time::interval(RECONCILE_INTERVAL);
+        loop {
+            tick.tick().await;
+            if let Err(e) = reconcile(&pool, audit_key.as_deref()).await {
+                tracing::warn!("vote reconciliation failed: {e}");
+            }
+        }
+    });
+}
+
+/// Recompute `votes_up`, `votes_down` and `score` from `registry_votes`,
+/// correct every entry that drifted and return what was corrected.
+///
+/// Candidates are found without locks, then each is re-counted under its row
+/// lock — the lock [`record`] takes — so a vote committed in between is never
+/// mistaken for drift.
+pub async fn reconcile(pool: &PgPool, audit_key: Option<&[u8]>) -> Result<Vec<Drift>, sqlx::Error> {
+    let mut corrected = Vec::new();
+
+    for target in Target::ALL {
+        let candidates: Vec<Uuid> = sqlx::query_scalar(&format!(
+            "SELECT t.id FROM {} t
+             LEFT JOIN (
+                 SELECT target_id,
+                        COUNT(*) FILTER (WHERE vote = 'up')   AS up,
+                        COUNT(*) FILTER (WHERE vote = 'down') AS down,
+                        SUM(CASE vote WHEN 'up' THEN weight ELSE -weight END) AS score
+                 FROM registry_votes WHERE target_type = $1 GROUP BY target_id
+             ) v ON v.target_id = t.id
+             WHERE t.votes_up <> COALESCE(v.up, 0) OR t.votes_down <> COALESCE(v.down, 0)
+                OR t.score <> COALESCE(v.score, 0)",
+            target.table()
+        ))
+        .bind(target.as_str())
+        .fetch_all(pool)
+        .await?;
+
+        for id in candidates {
+            let mut tx = pool.begin().await?;
+
+            let stored: Option<(i32, i32, i32)> = sqlx::query_as(&format!(
+                "SELECT votes_up, votes_down, score FROM {} WHERE id = $1 FOR UPDATE",
+                target.table()
+            ))
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?;
+            let Some((votes_up, votes_down, score)) = stored else { continue };
+
+            let (counted_up, counted_down, counted_score): (i32, i32, i32) = sqlx::query_as(
+                "SELECT COUNT(*) FILTER (WHERE vote = 'up')::INT,
+                        COUNT(*) FILTER (WHERE vote = 'down')::INT,
+                        COALESCE(SUM(CASE vote WHEN 'up' THEN weight ELSE -weight END), 0)::INT
+                 FROM registry_votes WHERE target_type = $1 AND target_id = $2",
+            )
+            .bind(target.as_str())
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await?;
+            if (counted_up, counted_down, counted_score) == (votes_up, votes_down, score) {
+                continue;
+            }
+
+            sqlx::query(&format!(
+                "UPDATE {} SET votes_up = $2, votes_down = $3, score = $4, updated_at = NOW() WHERE id = $1",
+                target.table()
+            ))
+            .bind(id)
+            .bind(counted_up)
+            .bind(counted_down)
+            .bind(counted_score)
+            .execute(&mut *tx)
+            .await?;
+
+            let drift = Drift {
+                target_type: target.as_str(),
+                id,
+                votes_up,
+                votes_down,
+                score,
+                counted_up,
+                counted_down,
+                counted_score,
+            };
+            audit::append(&mut tx, audit_key, "votes.reconciled", &id.to_string(), None, json!(drift)).await?;
+
+            tx.commit().await?;
+
+            tracing::warn!(
+                "Vote counters of {} {id} drifted: stored +{votes_up}/-{votes_down} score {score}, \
+                 counted +{counted_up}/-{counted_down} score {counted_score}",
+                target.as_str()
+            );
+            corrected.push(drift);
+        }
+    }
+
+    Ok(corrected)
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counter_deltas() {
+        assert_eq!(counter_delta(None, 0, Some("up"), 40), (1, 0, 40));
+        assert_eq!(counter_delta(Some("up"), 40, Some("down"), 45), (-1, 1, -85));
+        assert_eq!(counter_delta(Some("down"), 10, Some("up"), 10), (1, -1, 20));
+        assert_eq!(counter_delta(Some("down"), 10, None, 0), (0, -1, 10));
+        assert_eq!(Ballot::parse("retract").and_then(Ballot::stored), None);
+        assert_eq!(Ballot::parse("sideways"), None);
+    }
+}